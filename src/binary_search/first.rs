@@ -1,39 +1,22 @@
-//! A random implementation of binary search on a `Vec`.
+//! A random implementation of binary search on a `Vec`, or any other sorted sequence with
+//! random access (see `search::sequence`).
 
+use std::borrow::Borrow;
 
-pub fn binary_search<T>(data: &[T], value: &T) -> Option<usize>
+use crate::search::sequence::{self, RandomAccess};
+
+
+/// Return the index of the first occurrence of `value` in the sorted `data`.
+/// NOTE: The original implementation here indexed into `data` before checking whether it
+/// was empty, and gave up after a fixed number of iterations, so this now defers to
+/// `search::sequence`, which is correct for any length.
+pub fn binary_search<S, T>(data: S, value: &T) -> Option<usize>
     where
+        S: RandomAccess,
+        S::Item: Borrow<T>,
         T: PartialOrd + PartialEq
 {
-    let mut max_idx = data.len();
-    let mut min_idx = 0;
-    let mut mid = (max_idx + 1) / 2;
-    let mut result = &data[mid];
-    if result == value {
-        return Some(mid)
-    }
-    let mut i = 0;
-    while result != value && i < 10 {
-        i += 1;
-        mid = match result > value {
-            true => {
-                max_idx = mid;
-                match mid {
-                    1 => 0,
-                    _ => (min_idx + mid + 1) / 2
-                }
-            },
-            false => {
-                min_idx = mid;
-                (mid + max_idx + 1) / 2
-            }
-        };
-        result = &data[mid];
-    }
-    if result == value {
-        return Some(mid)
-    }
-    None
+    sequence::first_occurrence(data, value)
 }
 
 
@@ -54,4 +37,18 @@ mod test {
             assert_eq!(result, expected);
         }
     }
+
+    #[test]
+    fn binary_search_edge_cases() {
+        let empty: Vec<i32> = vec![];
+        assert_eq!(super::binary_search(&empty, &1), None);
+        let dups = vec![1_i32, 2, 2, 2, 2, 3];
+        assert_eq!(super::binary_search(&dups, &2), Some(1));
+        // needs more than 10 halvings
+        let large: Vec<i32> = (0..5000).collect();
+        for x in [0, 1, 2047, 4998, 4999] {
+            assert_eq!(super::binary_search(&large, &x), Some(x as usize));
+        }
+        assert_eq!(super::binary_search(&large, &5000), None);
+    }
 }
@@ -1,33 +1,60 @@
 //! Second pass at binary search, just to practice and to explore different
 //! implementations.
 
-use std::{cmp::Ordering, fmt::Debug};
+use std::{borrow::Borrow, cmp::Ordering};
 
+use crate::{error::Error, search::sequence::{self, RandomAccess}};
 
-fn binary_recurse<T>(values: &[T], x: &T, offset: usize) -> Result<Option<usize>, String>
+
+fn binary_recurse<S, T>(values: &S, x: &T, lo: usize, hi: usize) -> Result<Option<usize>, Error>
 where
-    T: Ord + PartialOrd
+    S: RandomAccess,
+    S::Item: Borrow<T>,
+    T: PartialOrd + ?Sized
 {
-    if values.is_empty() { return Ok(None) }
-    let n_values = values.len();
-    let mid = n_values / 2;
-    let x_mid = &values[mid];
-    match x.partial_cmp(x_mid) {
-        Some(Ordering::Equal) => Ok(Some(mid + offset)),
-        Some(Ordering::Less) => binary_recurse(&values[..mid], x, 0),
-        Some(Ordering::Greater) => binary_recurse(&values[mid+1..], x, mid+1),
-        None => Err(String::from("unable to compare values"))
+    if lo >= hi { return Ok(None) }
+    let mid = lo + (hi - lo) / 2;
+    let x_mid = values.at(mid);
+    match x.partial_cmp(x_mid.borrow()) {
+        // there may be an earlier occurrence to the left
+        Some(Ordering::Equal) => match binary_recurse(values, x, lo, mid)? {
+            Some(idx) => Ok(Some(idx)),
+            None => Ok(Some(mid)),
+        },
+        Some(Ordering::Less) => binary_recurse(values, x, lo, mid),
+        Some(Ordering::Greater) => binary_recurse(values, x, mid + 1, hi),
+        None => Err(Error::Incomparable)
     }
 }
 
 
-/// Perform binary search on a slice of monotincially increasing values
+/// Perform binary search on a sequence of monotincially increasing values
 /// and return an optional index of the first occurence.
-fn binary_search<T>(values: &[T], x: &T) -> Result<Option<usize>, String>
+/// In debug builds the sequence is first checked to actually be sorted, since searching
+/// unsorted input silently returns nonsense. That takes O(n) time, so release builds skip
+/// it; use `binary_search_checked()` to always check.
+fn binary_search<S, T>(values: S, x: &T) -> Result<Option<usize>, Error>
+where
+    S: RandomAccess + Copy,
+    S::Item: Borrow<T> + PartialOrd,
+    T: PartialOrd + ?Sized
+{
+    if cfg!(debug_assertions) {
+        sequence::check_sorted(values)?;
+    }
+    binary_recurse(&values, x, 0, values.len())
+}
+
+
+/// Same as `binary_search()`, but checks that `values` is sorted in every build.
+fn binary_search_checked<S, T>(values: S, x: &T) -> Result<Option<usize>, Error>
 where
-    T: Debug + Ord + PartialOrd
+    S: RandomAccess + Copy,
+    S::Item: Borrow<T> + PartialOrd,
+    T: PartialOrd + ?Sized
 {
-    binary_recurse(values, x, 0)
+    sequence::check_sorted(values)?;
+    binary_recurse(&values, x, 0, values.len())
 }
 
 
@@ -36,7 +63,7 @@ mod tests {
 
     #[test]
     fn binary_search() {
-        let data = vec![1_i32, 3, 4, 5, 7, 1, 15, 19];
+        let data = vec![1_i32, 3, 4, 5, 7, 10, 15, 19];
         let test_cases = vec![
             (1, Ok(Some(0))),
             (3, Ok(Some(1))),
@@ -48,4 +75,59 @@ mod tests {
             assert_eq!(result, expected);
         }
     }
+
+    #[test]
+    fn binary_search_deep() {
+        // indices are only right if the offset is carried through every level
+        let data: Vec<i32> = (0..1000).map(|i| i * 2).collect();
+        for (i, x) in data.iter().enumerate() {
+            assert_eq!(super::binary_search(&data, x), Ok(Some(i)));
+            assert_eq!(super::binary_search(&data, &(x + 1)), Ok(None));
+        }
+        let empty: Vec<i32> = vec![];
+        assert_eq!(super::binary_search(&empty, &1), Ok(None));
+    }
+
+    #[test]
+    fn binary_search_first_occurrence() {
+        let data = vec![1_i32, 2, 2, 2, 2, 2, 2, 3, 3];
+        assert_eq!(super::binary_search(&data, &2), Ok(Some(1)));
+        assert_eq!(super::binary_search(&data, &3), Ok(Some(7)));
+    }
+
+    #[test]
+    fn binary_search_checked() {
+        use crate::error::Error;
+        let data = vec![1_i32, 3, 4, 5, 7, 1, 15, 19];
+        assert_eq!(super::binary_search_checked(&data, &15), Err(Error::Unsorted { index: 5 }));
+        let data = vec![1_i32, 3, 4, 5, 7, 10, 15, 19];
+        assert_eq!(super::binary_search_checked(&data, &15), Ok(Some(6)));
+    }
+
+    #[test]
+    fn binary_search_vec_deque() {
+        use std::collections::VecDeque;
+        // pushing to the front wraps the contents around the end of the buffer
+        let mut data: VecDeque<i32> = VecDeque::with_capacity(32);
+        data.extend(20..30);
+        for x in (10..20).rev() {
+            data.push_front(x);
+        }
+        assert!(!data.as_slices().1.is_empty());
+        for x in 0..40 {
+            let expected = if (10..30).contains(&x) { Some(x as usize - 10) } else { None };
+            assert_eq!(super::binary_search(&data, &x), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn binary_search_debug_check() {
+        use crate::error::Error;
+        let data = vec![1_i32, 3, 4, 5, 7, 1, 15, 19];
+        if cfg!(debug_assertions) {
+            assert_eq!(super::binary_search(&data, &15), Err(Error::Unsorted { index: 5 }));
+        } else {
+            assert_eq!(super::binary_search(&data, &15), Ok(Some(6)));
+        }
+    }
 }
@@ -0,0 +1,48 @@
+//! The error type shared by the modules of this crate, in place of ad hoc `String`s.
+
+use std::fmt;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Two values could not be compared, e.g. a float `NaN`.
+    Incomparable,
+    /// The input had to be sorted, but `values[index - 1] > values[index]`.
+    Unsorted { index: usize },
+    IndexOutOfRange { index: usize, len: usize },
+    /// A vertex, or element, that was never added to the structure.
+    UnknownVertex,
+    /// The interval given to a search doesn't contain what it's looking for.
+    NotBracketed,
+    /// An iterative method ran out of iterations before reaching its tolerance.
+    NotConverged { iterations: usize },
+    /// A constraint contradicts the ones that were already recorded.
+    Contradiction,
+    /// An edge that isn't in the graph.
+    UnknownEdge { u: usize, v: usize },
+}
+
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Incomparable => write!(f, "unable to compare values"),
+            Error::Unsorted { index } => {
+                write!(f, "input is not sorted: value at index {index} is out of order")
+            },
+            Error::IndexOutOfRange { index, len } => {
+                write!(f, "index {index} is out of range for length {len}")
+            },
+            Error::UnknownVertex => write!(f, "unknown vertex"),
+            Error::NotBracketed => write!(f, "the interval does not bracket a solution"),
+            Error::NotConverged { iterations } => {
+                write!(f, "did not converge within {iterations} iterations")
+            },
+            Error::Contradiction => write!(f, "the constraint contradicts earlier ones"),
+            Error::UnknownEdge { u, v } => write!(f, "unknown edge ({u}, {v})"),
+        }
+    }
+}
+
+
+impl std::error::Error for Error {}
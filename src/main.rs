@@ -23,9 +23,13 @@
 //! - Adaptability -- works with different distance or weight metrics.
 //! - Visualization and analysis.
 mod binary_search;
+mod error;
 mod min_spanning_tree;
 mod path_search;
+mod search;
+mod sorted_collections;
 mod sorting;
+mod spatial;
 mod union_find;
 
 
@@ -1,52 +1,16 @@
 //! An implementation of Boruvka's algorithm that takes an edge list as input.
 
+use crate::union_find::DisjointSet;
+
 pub struct Graph {
     edges: Vec<(usize, usize, f32)>,
     vertices: Vec<usize>,
 }
 
 
-fn find_set_recursive(vertex: usize, components: &mut Vec<usize>) -> usize {
-    if components[vertex] != vertex {
-        components[vertex] = find_set_recursive(components[vertex], components);
-    }
-    components[vertex]
-}
-
-
-fn find_set_iterative(mut vertex: usize, components: &mut Vec<usize>) -> usize {
-    let mut root = vertex;
-    while components[root] != root {
-        root = components[root];
-    }
-    while components[vertex] != root {
-        let next = components[vertex];
-        components[vertex] = root;
-        vertex = next;
-    }
-    root
-}
-
-
-fn union_components(
-    mut comp_a: usize,
-    mut comp_b: usize,
-    components: &mut Vec<usize>,
-    ranks: &mut Vec<usize>
-) {
-    if ranks[comp_a] < ranks[comp_b] {
-        std::mem::swap(&mut comp_a, &mut comp_b);
-    }
-    if ranks[comp_a] == ranks[comp_b] {
-        ranks[comp_a] += 1;
-    }
-    components[comp_b] = comp_a;
-}
-
-
 /// This implementation expects and edge list, along with a vector of vertices, rather
 /// than an adjaceny matrix, and the the time complexity could be broken down as follows:
-/// - Constructing the components and ranks are each O(V)
+/// - Constructing the components is O(V)
 /// - Outer loop: because each iteration cuts the number of components at least in half,
 ///   due to the merging of components, the worst case complexity is O(logV), where V is
 ///   the number of vertices.
@@ -56,10 +20,9 @@ fn union_components(
 ///     - Check if all are None: O(V) because |min_edges| = |components| = |vertices|
 ///     - Second inner loop (over min edges): this has multiple operations to consider:
 ///         - the loop itself iterates over `min_edges`, so this is O(V)
-///         - `find_set_iterative()`: This uses path compression, which is technically
+///         - `DisjointSet::union()`: This uses path compression, which is technically
 ///           O(alhpa(V)), where alpha(.) is the "inverse" Ackermann function, which grows
 ///           so slowly that it can be considered constant: O(1)
-///         - `union_components()`: clearly constant O(1)
 ///
 ///               v-{first inner x outer}
 /// Total: O(V) + O(ElogV) + O(VlogV)
@@ -67,13 +30,12 @@ fn union_components(
 /// Asymptotic is O(ElogV) <= O(V^2logV)
 pub fn build_mst(graph: Graph) -> Vec<(usize, usize)> {
     let mut mst = vec![];
-    let mut components = graph.vertices.clone();
-    let mut ranks: Vec<usize> = vec![1; components.len()];
+    let mut components = DisjointSet::new(graph.vertices.len());
     loop {
         // Initialize the cheapest edge for each component to "None"
         let mut min_edges: Vec<Option<usize>> = vec![None; components.len()];
         for (i, (u, v, weight)) in graph.edges.iter().enumerate() {  // E iterations
-            if components[*u] == components[*v] {
+            if components.connected(*u, *v) {
                 continue
             }
             match min_edges[*u] {
@@ -103,14 +65,10 @@ pub fn build_mst(graph: Graph) -> Vec<(usize, usize)> {
                 None => continue,
                 Some(i) => {
                     let e = &graph.edges[i];
-                    let comp_a = find_set_iterative(e.0, &mut components);
-                    let comp_b = find_set_iterative(e.1, &mut components);
-                    if comp_a == comp_b {
-                        continue
-                    }
-                    mst.push((e.0, e.1));
                     // union the two components
-                    union_components(comp_a, comp_b, &mut components, &mut ranks);
+                    if components.union(e.0, e.1) {
+                        mst.push((e.0, e.1));
+                    }
                 }
             }
         }
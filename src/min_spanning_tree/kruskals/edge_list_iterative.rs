@@ -1,5 +1,9 @@
 //! A more memory-efficient implementation of Kruskal's algoritm compared to the one
 //! found in [super::edge_list_recursive]: this one requires constant memory.
+//! The union-find is `union_find::DisjointSet`, whose `find()` is iterative.
+
+use crate::union_find::DisjointSet;
+
 
 pub struct Graph {
     pub edges: Vec<(usize, usize, f32)>,
@@ -7,49 +11,16 @@ pub struct Graph {
 }
 
 
-fn find_set(mut vertex: usize, parents: &mut Vec<usize>) -> usize {
-    let mut root = vertex;
-    while parents[root] != root {
-        root = parents[root];
-    }
-    while parents[vertex] != root {
-        let parent = parents[vertex];
-        parents[vertex] = root;
-        vertex = parent;
-    }
-    parents[root]
-}
-
-
-fn union(a: usize, b: usize, parents: &mut Vec<usize>, ranks: &mut Vec<usize>) {
-    let mut root1 = find_set(a, parents);
-    let mut root2 = find_set(b, parents);
-    if root1 == root2 { return }
-    if ranks[root1] < ranks[root2] {
-        std::mem::swap(&mut root1, &mut root2);
-    }
-    parents[root2] = root1;
-    if ranks[root1] == ranks[root2] {
-        ranks[root1] += 1;
-    }
-}
-
-
 pub fn build_mst(graph: Graph) -> Vec<(usize, usize)> {
     let mut mst = vec![];
-    let size = graph.vertices.len();
-    // init each vertex as it's own parent
-    let mut parents: Vec<usize> = (0..size).collect();
-    let mut ranks: Vec<usize> = vec![1; size];
+    // init each vertex as it's own set
+    let mut ds = DisjointSet::new(graph.vertices.len());
     // 1. sort edges
     let mut sorted_edges: Vec<&(usize, usize, f32)> = graph.edges.iter().collect();
     sorted_edges.sort_unstable_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
     for edge in sorted_edges {
-        let root1 = find_set(edge.0, &mut parents);
-        let root2 = find_set(edge.1, &mut parents);
-        if root1 != root2 {
+        if ds.union(edge.0, edge.1) {
             mst.push((edge.0, edge.1));
-            union(root1, root2, &mut parents, &mut ranks);
         }
     }
     mst
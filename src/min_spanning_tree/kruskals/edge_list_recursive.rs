@@ -4,6 +4,8 @@
 //! This avoids the complexities that are inherent in rust with a graph of
 //! linked nodes, such as `Rc<RefCell<Vertex>>`.
 
+use crate::union_find::DisjointSet;
+
 type Edge = (usize, usize, f32);
 
 
@@ -13,49 +15,16 @@ pub struct Graph {
 }
 
 
-fn find_set(vertex: usize, parents: &mut Vec<usize>) -> usize {
-    if vertex != parents[vertex] {
-        parents[vertex] = find_set(parents[vertex], parents);
-    }
-    return parents[vertex]
-}
-
-
-fn union(
-    mut u: usize,
-    mut v: usize,
-    parents: &mut Vec<usize>,
-    ranks: &mut Vec<usize>
-) {
-    let root1 = find_set(u, parents);
-    let root2 = find_set(v, parents);
-    if root1 == root2 {
-        return
-    }
-    if ranks[root1] < ranks[root2] {
-        std::mem::swap(&mut u, &mut v);
-    }
-    parents[root2] = root1;
-    if ranks[root1] == ranks[root2] {
-        ranks[root1] += 1;
-    }
-}
-
-
 pub fn build_mst(graph: Graph) -> Vec<(usize, usize)> {
     let mut forest = vec![];
-    let mut parents: Vec<usize> = graph.vertices.clone();
-    let mut ranks: Vec<usize> = vec![0; graph.vertices.len()];
+    let mut ds = DisjointSet::new(graph.vertices.len());
     let mut edges_sorted: Vec<&Edge> = graph.edges
         .iter()
         .collect();
     edges_sorted.sort_unstable_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
     for edge in edges_sorted {
-        let root1 = find_set(edge.0, &mut parents);
-        let root2 = find_set(edge.1, &mut parents);
-        if root1 != root2 {
+        if ds.union(edge.0, edge.1) {
             forest.push((edge.0, edge.1));
-            union(root1, root2, &mut parents, &mut ranks);
         }
     }
     forest
@@ -1,6 +1,8 @@
-//! Revisiting Kruskals algorithm for learning/memory reinforcement. This one uses the
-//! recursive version of `find()` in the union-find algorithm.
+//! Revisiting Kruskals algorithm for learning/memory reinforcement. This one originally
+//! used the recursive version of `find()` in the union-find algorithm; it now shares
+//! `union_find::DisjointSet` with the other edge list implementations.
 
+use crate::union_find::DisjointSet;
 
 struct Graph {
     edges: Vec<(usize, usize, f32)>,
@@ -8,30 +10,6 @@ struct Graph {
 }
 
 
-fn find(idx: usize, parents: &mut [usize], ranks: &mut [usize]) -> Option<usize> {
-    if idx != parents[idx] {
-        parents[idx] = find(parents[idx], parents, ranks)?;
-    }
-    Some(parents[idx])
-}
-
-
-fn union(a: usize, b: usize, parents: &mut [usize], ranks: &mut [usize]) -> Option<()> {
-    let mut root1 = find(a, parents, ranks)?;
-    let mut root2 = find(b, parents, ranks)?;
-    if root1 == root2 {
-        return Some(())
-    }
-    if ranks[root1] < ranks[root2] {
-        std::mem::swap(&mut root1, &mut root2);
-    }
-    if ranks[root1] == ranks[root2] {
-        ranks[root1] += 1;
-    }
-    parents[root2] = root1;
-    Some(())
-}
-
 /// The approach to Kruskal's is to utilize a disjoint set with the union-find algorithm
 /// (with path compression) to build a min spanning tree from the bottom up, merging
 /// subtrees at their roots, until ending up with a complete MST. But this algorithm does
@@ -43,17 +21,13 @@ fn union(a: usize, b: usize, parents: &mut [usize], ranks: &mut [usize]) -> Opti
 /// 4. if they have different roots, merge the roots and add the edge to the MST
 fn build_mst(graph: &Graph) -> Vec<(usize, usize)> {
     let mut mst = vec![];
-    let mut parents = graph.vertices.clone();
-    let mut ranks = vec![0_usize; parents.len()];
+    let mut ds = DisjointSet::new(graph.vertices.len());
     let mut sorted_edges = graph.edges.clone();
     sorted_edges.sort_unstable_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
     for edge in &sorted_edges {
-        let root1 = find(edge.0, &mut parents, &mut ranks).unwrap();
-        let root2 = find(edge.1, &mut parents, &mut ranks).unwrap();
-        if root1 == root2 {
+        if !ds.union(edge.0, edge.1) {
             continue
         }
-        union(root1, root2, &mut parents, &mut ranks);
         mst.push((edge.0, edge.1));
     }
     mst
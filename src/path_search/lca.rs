@@ -0,0 +1,167 @@
+//! Tarjan's offline lowest common ancestor: answering a batch of "which node is the
+//! deepest common ancestor of `u` and `v`?" queries on a rooted tree, such as a spanning
+//! tree from `min_spanning_tree`, in O((n + q) α(n)) time overall.
+//!
+//! The tree is walked depth first. Once a child's subtree is done, it's united with the
+//! set of its parent, whose `ancestor` is the parent. So while a node `u` is being
+//! finished, every node `v` that has already been finished is in the set of the deepest
+//! node on `u`'s path from the root whose subtree holds `v`: the LCA of `u` and `v`.
+//! Each query is answered when the second of its nodes is finished.
+//! The walk is iterative, so deep trees (e.g. paths) can't overflow the stack.
+
+use crate::{error::Error, union_find::DisjointSet};
+
+
+/// The lowest common ancestor of every pair in `queries`, in the tree on the vertices
+/// `0..n` made of the undirected `edges`, rooted at `root`. `edges` must form a tree, or
+/// a forest, in which case the pairs that aren't both in the tree of `root` get `None`.
+pub fn lowest_common_ancestors(
+    n: usize,
+    edges: &[(usize, usize)],
+    root: usize,
+    queries: &[(usize, usize)],
+) -> Result<Vec<Option<usize>>, Error> {
+    let vertices = edges.iter().chain(queries).flat_map(|(u, v)| [*u, *v]);
+    if let Some(x) = vertices.chain([root]).find(|x| *x >= n) {
+        return Err(Error::IndexOutOfRange { index: x, len: n })
+    }
+    let mut adjacent = vec![vec![]; n];
+    for (u, v) in edges {
+        adjacent[*u].push(*v);
+        adjacent[*v].push(*u);
+    }
+    // `pending[u]`: the queries involving `u`, as (index of the query, the other node)
+    let mut pending: Vec<Vec<(usize, usize)>> = vec![vec![]; n];
+    for (i, (u, v)) in queries.iter().enumerate() {
+        pending[*u].push((i, *v));
+        pending[*v].push((i, *u));
+    }
+    let mut answers = vec![None; queries.len()];
+    let mut ds = DisjointSet::new(n);
+    // `ancestor[root]`: the tree node that the set of `root` currently stands for
+    let mut ancestor: Vec<usize> = (0..n).collect();
+    let mut visited = vec![false; n];
+    let mut finished = vec![false; n];
+    // (node, its parent, the index of its next child to visit)
+    let mut stack: Vec<(usize, Option<usize>, usize)> = vec![(root, None, 0)];
+    visited[root] = true;
+    while let Some((node, parent, next)) = stack.last_mut() {
+        let node = *node;
+        if let Some(child) = adjacent[node].get(*next).copied() {
+            *next += 1;
+            if Some(child) != *parent && !visited[child] {
+                visited[child] = true;
+                stack.push((child, Some(node), 0));
+            }
+            continue
+        }
+        let parent = *parent;
+        stack.pop();
+        finished[node] = true;
+        for (i, other) in &pending[node] {
+            if finished[*other] {
+                answers[*i] = Some(ancestor[ds.find(*other)]);
+            }
+        }
+        if let Some(parent) = parent {
+            ds.union(parent, node);
+            let set = ds.find(parent);
+            ancestor[set] = parent;
+        }
+    }
+    Ok(answers)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::{error::Error, min_spanning_tree::kruskals::edge_list_iterative};
+
+    /// LCA by walking up from the deeper node, from parents and depths found breadth
+    /// first.
+    fn naive(
+        n: usize,
+        edges: &[(usize, usize)],
+        root: usize,
+        u: usize,
+        v: usize,
+    ) -> Option<usize> {
+        let mut adjacent = vec![vec![]; n];
+        for (a, b) in edges {
+            adjacent[*a].push(*b);
+            adjacent[*b].push(*a);
+        }
+        let mut parents: Vec<Option<usize>> = vec![None; n];
+        let mut depths: Vec<Option<usize>> = vec![None; n];
+        depths[root] = Some(0);
+        let mut queue = std::collections::VecDeque::from([root]);
+        while let Some(x) = queue.pop_front() {
+            for y in &adjacent[x] {
+                if depths[*y].is_none() {
+                    depths[*y] = depths[x].map(|d| d + 1);
+                    parents[*y] = Some(x);
+                    queue.push_back(*y);
+                }
+            }
+        }
+        let (mut u, mut v) = (u, v);
+        let (mut du, mut dv) = (depths[u]?, depths[v]?);
+        while du > dv { u = parents[u]?; du -= 1; }
+        while dv > du { v = parents[v]?; dv -= 1; }
+        while u != v {
+            u = parents[u]?;
+            v = parents[v]?;
+        }
+        Some(u)
+    }
+
+    #[test]
+    fn lowest_common_ancestors() {
+        //        0
+        //      /   \
+        //     1     2
+        //    / \     \
+        //   3   4     5
+        //       |
+        //       6        7 (not in the tree)
+        let edges = [(0, 1), (0, 2), (3, 1), (1, 4), (2, 5), (4, 6)];
+        let queries = [(3, 6), (6, 5), (4, 4), (6, 4), (0, 6), (5, 2), (7, 1)];
+        let expected = vec![Some(1), Some(0), Some(4), Some(4), Some(0), Some(2), None];
+        assert_eq!(super::lowest_common_ancestors(8, &edges, 0, &queries), Ok(expected));
+        // rooted elsewhere
+        let rerooted = super::lowest_common_ancestors(8, &edges, 4, &[(3, 5), (0, 6)]);
+        assert_eq!(rerooted, Ok(vec![Some(1), Some(4)]));
+        assert_eq!(
+            super::lowest_common_ancestors(8, &edges, 0, &[(0, 8)]),
+            Err(Error::IndexOutOfRange { index: 8, len: 8 }),
+        );
+    }
+
+    #[test]
+    fn minimum_spanning_tree() {
+        let n = 300;
+        let mut rng = StdRng::seed_from_u64(107);
+        let edges: Vec<(usize, usize, f32)> = (0..1500)
+            .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0.0..100.0)))
+            .collect();
+        let graph = edge_list_iterative::Graph { edges, vertices: (0..n).collect() };
+        let mst = edge_list_iterative::build_mst(graph);
+        let queries: Vec<(usize, usize)> = (0..500)
+            .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+            .collect();
+        let answers = super::lowest_common_ancestors(n, &mst, 7, &queries).unwrap();
+        for ((u, v), answer) in queries.iter().zip(answers) {
+            assert_eq!(answer, naive(n, &mst, 7, *u, *v));
+        }
+    }
+
+    #[test]
+    fn deep_path() {
+        let n = 1_000_000;
+        let edges: Vec<(usize, usize)> = (1..n).map(|i| (i - 1, i)).collect();
+        let answers = super::lowest_common_ancestors(n, &edges, 0, &[(n - 1, n / 2), (3, n - 7)]);
+        assert_eq!(answers, Ok(vec![Some(n / 2), Some(3)]));
+    }
+}
@@ -2,3 +2,4 @@
 //! as they relate to paths in a graph data structure.
 
 pub mod hamiltonian;
+pub mod lca;
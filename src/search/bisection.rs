@@ -0,0 +1,250 @@
+//! Searching over the answer, rather than over a sorted sequence: given a monotone
+//! predicate (false up to some threshold, true from there on), find the threshold.
+//! This is the same halving as `binary_search::second`, except the "sequence" is a range
+//! of candidate answers, and looking up an element means evaluating the predicate, e.g.
+//! "can `x` servers handle the load?".
+//! For unimodal functions (decreasing, then increasing), ternary and golden-section
+//! search find the minimum by shrinking the interval in a similar way.
+
+use crate::error::Error;
+
+
+/// When to stop shrinking an interval `[lo, hi]` of reals: once its width is at most
+/// `abs + rel * |midpoint|`, or with an error after `max_iter` iterations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub abs: f64,
+    pub rel: f64,
+    pub max_iter: usize,
+}
+
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self { abs: 1e-9, rel: 1e-12, max_iter: 200 }
+    }
+}
+
+
+impl Tolerance {
+    fn converged(&self, lo: f64, hi: f64) -> bool {
+        let mid = lo + (hi - lo) / 2.;
+        hi - lo <= self.abs + self.rel * mid.abs()
+    }
+}
+
+
+fn min_true_recurse<P>(lo: i64, hi: i64, pred: &mut P) -> i64
+where
+    P: FnMut(i64) -> bool
+{
+    if lo >= hi { return hi }
+    // the difference can overflow an i64, but always fits in a u64
+    let mid = lo.wrapping_add((hi.wrapping_sub(lo) as u64 / 2) as i64);
+    match pred(mid) {
+        true => min_true_recurse(lo, mid, pred),
+        false => min_true_recurse(mid + 1, hi, pred),
+    }
+}
+
+
+/// The smallest `x` in `lo..hi` for which `pred(x)` is true, where `pred` is monotone.
+/// `pred` is evaluated O(log(hi - lo)) times.
+pub fn min_true<P>(lo: i64, hi: i64, mut pred: P) -> Option<i64>
+where
+    P: FnMut(i64) -> bool
+{
+    let x = min_true_recurse(lo, hi, &mut pred);
+    if x < hi { Some(x) } else { None }
+}
+
+
+/// The largest `x` in `lo..hi` for which `pred(x)` is false, where `pred` is monotone.
+pub fn max_false<P>(lo: i64, hi: i64, mut pred: P) -> Option<i64>
+where
+    P: FnMut(i64) -> bool
+{
+    let x = min_true_recurse(lo, hi, &mut pred);
+    if x > lo { Some(x - 1) } else { None }
+}
+
+
+/// `[lo, hi]` has to be an interval: neither end `NaN`, and `lo <= hi`.
+fn check_interval(lo: f64, hi: f64) -> Result<(), Error> {
+    if lo.is_nan() || hi.is_nan() || lo > hi {
+        return Err(Error::NotBracketed)
+    }
+    Ok(())
+}
+
+
+/// Bisection over the reals: the threshold in `[lo, hi]` where the monotone `pred`
+/// switches from false to true, to within `tol`. The returned value always satisfies
+/// `pred`. For a root of an increasing function `f`, use `|x| f(x) >= 0.`.
+pub fn bisect<P>(mut lo: f64, mut hi: f64, mut pred: P, tol: Tolerance) -> Result<f64, Error>
+where
+    P: FnMut(f64) -> bool
+{
+    check_interval(lo, hi)?;
+    if !pred(hi) {
+        return Err(Error::NotBracketed)
+    }
+    if pred(lo) {
+        return Ok(lo)
+    }
+    // invariant: `pred(lo)` is false and `pred(hi)` is true
+    for _ in 0..tol.max_iter {
+        if tol.converged(lo, hi) {
+            return Ok(hi)
+        }
+        let mid = lo + (hi - lo) / 2.;
+        if mid <= lo || mid >= hi {
+            // the interval can't be split any further in floating point
+            return Ok(hi)
+        }
+        match pred(mid) {
+            true => hi = mid,
+            false => lo = mid,
+        }
+    }
+    Err(Error::NotConverged { iterations: tol.max_iter })
+}
+
+
+/// The minimum of a function that is unimodal on `[lo, hi]`, found by comparing it at
+/// the two points that split the interval into thirds, and dropping the third that can't
+/// contain the minimum. Costs two evaluations for every reduction of the interval by 1/3.
+pub fn ternary_search<F>(mut lo: f64, mut hi: f64, mut f: F, tol: Tolerance) -> Result<f64, Error>
+where
+    F: FnMut(f64) -> f64
+{
+    check_interval(lo, hi)?;
+    for _ in 0..tol.max_iter {
+        if tol.converged(lo, hi) {
+            return Ok(lo + (hi - lo) / 2.)
+        }
+        let third = (hi - lo) / 3.;
+        let (m1, m2) = (lo + third, hi - third);
+        if f(m1) < f(m2) {
+            hi = m2;
+        } else {
+            lo = m1;
+        }
+    }
+    Err(Error::NotConverged { iterations: tol.max_iter })
+}
+
+
+/// Same as `ternary_search()`, but the two inner points split the interval by the golden
+/// ratio. After dropping an end, one of the old inner points is exactly where one of the
+/// new ones has to be, so every iteration costs only one evaluation, and shrinks the
+/// interval to 0.618 of its size (compared to 0.667 for two evaluations).
+pub fn golden_section<F>(mut lo: f64, mut hi: f64, mut f: F, tol: Tolerance) -> Result<f64, Error>
+where
+    F: FnMut(f64) -> f64
+{
+    check_interval(lo, hi)?;
+    let inv_phi = (5_f64.sqrt() - 1.) / 2.;
+    let mut c = hi - inv_phi * (hi - lo);
+    let mut d = lo + inv_phi * (hi - lo);
+    let (mut fc, mut fd) = (f(c), f(d));
+    for _ in 0..tol.max_iter {
+        if tol.converged(lo, hi) {
+            return Ok(lo + (hi - lo) / 2.)
+        }
+        if fc < fd {
+            hi = d;
+            (d, fd) = (c, fc);
+            c = hi - inv_phi * (hi - lo);
+            fc = f(c);
+        } else {
+            lo = c;
+            (c, fc) = (d, fd);
+            d = lo + inv_phi * (hi - lo);
+            fd = f(d);
+        }
+    }
+    Err(Error::NotConverged { iterations: tol.max_iter })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use super::Tolerance;
+
+    #[test]
+    fn min_true() {
+        // fewest servers, each handling 137 requests, to handle 10_000 requests
+        let servers = super::min_true(0, 1_000, |x| x * 137 >= 10_000);
+        assert_eq!(servers, Some(73));
+        // (lo, hi, threshold, expected)
+        let cases = vec![
+            (0, 10, 0, Some(0)),
+            (0, 10, 9, Some(9)),
+            (0, 10, 10, None),
+            (5, 5, 5, None),
+            (-20, 20, -7, Some(-7)),
+            (i64::MIN, i64::MAX, i64::MIN, Some(i64::MIN)),
+            (i64::MIN, i64::MAX, 123, Some(123)),
+            (i64::MIN, i64::MAX, i64::MAX - 1, Some(i64::MAX - 1)),
+        ];
+        for (lo, hi, threshold, expected) in cases {
+            assert_eq!(super::min_true(lo, hi, |x| x >= threshold), expected);
+        }
+    }
+
+    #[test]
+    fn max_false() {
+        assert_eq!(super::max_false(0, 100, |x| x * x > 50), Some(7));
+        assert_eq!(super::max_false(0, 100, |_| true), None);
+        assert_eq!(super::max_false(0, 100, |_| false), Some(99));
+    }
+
+    #[test]
+    fn bisect() {
+        let tol = Tolerance::default();
+        let sqrt2 = super::bisect(0., 2., |x| x * x >= 2., tol).unwrap();
+        assert!((sqrt2 - 2_f64.sqrt()).abs() <= 1e-9);
+        assert!(sqrt2 * sqrt2 >= 2.);
+        // relative tolerance for large thresholds
+        let tol = Tolerance { abs: 0., rel: 1e-10, max_iter: 200 };
+        let big = super::bisect(0., 1e20, |x| x >= 3.3e18, tol).unwrap();
+        assert!((big - 3.3e18).abs() <= 3.3e18 * 1e-10);
+        assert_eq!(super::bisect(0., 1., |x| x > 2., tol), Err(Error::NotBracketed));
+        assert_eq!(super::bisect(1., 0., |x| x > 0.5, tol), Err(Error::NotBracketed));
+        let few = Tolerance { abs: 1e-12, rel: 0., max_iter: 10 };
+        let result = super::bisect(0., 1., |x| x >= 0.3, few);
+        assert_eq!(result, Err(Error::NotConverged { iterations: 10 }));
+    }
+
+    #[test]
+    fn unimodal() {
+        let tol = Tolerance { abs: 1e-8, rel: 0., max_iter: 500 };
+        let mut ternary_evals = 0;
+        let ternary = super::ternary_search(-10., 10., |x| {
+            ternary_evals += 1;
+            (x - 1.5).powi(2) + 3.
+        }, tol).unwrap();
+        let mut golden_evals = 0;
+        let golden = super::golden_section(-10., 10., |x| {
+            golden_evals += 1;
+            (x - 1.5).powi(2) + 3.
+        }, tol).unwrap();
+        assert!((ternary - 1.5).abs() < 1e-7);
+        assert!((golden - 1.5).abs() < 1e-7);
+        assert!(golden_evals < ternary_evals / 2, "{golden_evals} vs {ternary_evals}");
+        let few = Tolerance { abs: 1e-8, rel: 0., max_iter: 5 };
+        let result = super::golden_section(-10., 10., |x| x * x, few);
+        assert_eq!(result, Err(Error::NotConverged { iterations: 5 }));
+    }
+
+    #[test]
+    fn unimodal_not_bracketed() {
+        let tol = Tolerance::default();
+        for (lo, hi) in [(1., -1.), (f64::NAN, 1.), (0., f64::NAN)] {
+            assert_eq!(super::ternary_search(lo, hi, |x| x * x, tol), Err(Error::NotBracketed));
+            assert_eq!(super::golden_section(lo, hi, |x| x * x, tol), Err(Error::NotBracketed));
+        }
+    }
+}
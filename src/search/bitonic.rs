@@ -0,0 +1,94 @@
+//! Searching a bitonic array: one that strictly increases up to a peak and then strictly
+//! decreases, e.g. `[1, 4, 8, 9, 7, 3, 2]`. First binary search for the peak, and then
+//! binary search the increasing and the decreasing side separately.
+
+use crate::search::{bisection, bounds};
+
+
+/// The index of the largest value. `values` has to be bitonic, so that there are no two
+/// equal neighbours; otherwise the result is some local maximum.
+pub fn peak<T: Ord>(values: &[T]) -> Option<usize> {
+    if values.is_empty() {
+        return None
+    }
+    // the peak is the first index where the values stop increasing
+    let last = values.len() as i64 - 1;
+    let peak = bisection::min_true(0, last, |i| {
+        let i = i as usize;
+        values[i] > values[i+1]
+    });
+    Some(peak.map_or(values.len() - 1, |i| i as usize))
+}
+
+
+/// Return the index of the first occurrence of `x` in the bitonic array `values`. A value
+/// can appear at most twice: once on each side of the peak.
+pub fn search_bitonic<T: Ord>(values: &[T], x: &T) -> Option<usize> {
+    let peak = peak(values)?;
+    if let Some(idx) = bounds::first_occurrence(&values[..=peak], x) {
+        return Some(idx)
+    }
+    // the decreasing side is sorted in reverse, so search for the first value not
+    // greater than `x`
+    let decreasing = &values[peak+1..];
+    let idx = bounds::partition_point(decreasing, |v| v > x);
+    match decreasing.get(idx) {
+        Some(v) if v == x => Some(peak + 1 + idx),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn peak() {
+        let cases: Vec<Vec<i32>> = vec![
+            vec![],
+            vec![1],
+            vec![1, 2, 3],
+            vec![3, 2, 1],
+            vec![1, 4, 8, 9, 7, 3, 2],
+            vec![1, 9, 2],
+        ];
+        let expected: Vec<Option<usize>> = vec![None, Some(0), Some(2), Some(0), Some(3), Some(1)];
+        for (case, expect) in cases.iter().zip(&expected) {
+            assert_eq!(super::peak(case), *expect);
+        }
+    }
+
+    #[test]
+    fn search_bitonic() {
+        let data = vec![1_i32, 4, 8, 9, 7, 3, 2];
+        let test_cases = vec![
+            (1, Some(0)),
+            (4, Some(1)),
+            (9, Some(3)),
+            (7, Some(4)),
+            (2, Some(6)),
+            (5, None),
+            (0, None),
+            (10, None),
+        ];
+        for (value, expected) in test_cases {
+            assert_eq!(super::search_bitonic(&data, &value), expected);
+        }
+        // values on both sides of the peak return the left one
+        let data = vec![1_i32, 3, 5, 4, 3, 1];
+        assert_eq!(super::search_bitonic(&data, &3), Some(1));
+        assert_eq!(super::search_bitonic(&data, &4), Some(3));
+    }
+
+    #[test]
+    fn matches_linear_scan() {
+        for peak in 0..30 {
+            let mut data: Vec<i32> = (0..=peak).map(|i| 2 * i).collect();
+            data.extend((0..30 - peak).rev().map(|i| 2 * i + 1));
+            for x in -1..70 {
+                let expected = data.iter().position(|v| *v == x);
+                assert_eq!(super::search_bitonic(&data, &x), expected, "{data:?} {x}");
+            }
+        }
+    }
+}
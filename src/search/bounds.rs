@@ -0,0 +1,172 @@
+//! Binary search in terms of bounds, in the style of C++'s `<algorithm>`.
+//! Everything is built on `partition_point()`, which needs only a predicate that is true
+//! for a prefix of the slice and false for the rest. The other functions assume `values`
+//! is sorted in ascending order, and return insertion points rather than failing, so
+//! they are well-defined for empty slices and for values that aren't present.
+//! These are the slice versions of the searches in `search::sequence`, which work on
+//! any sequence with random access.
+
+use std::ops::{Bound, Range, RangeBounds};
+
+use crate::error::Error;
+use crate::search::sequence;
+
+
+/// The index of the first element for which `pred` is false, or `values.len()` if there
+/// is none. `pred` must be true for a (possibly empty) prefix of `values` and false for
+/// the remainder.
+pub fn partition_point<T, P>(values: &[T], pred: P) -> usize
+where
+    P: FnMut(&T) -> bool
+{
+    sequence::partition_point(values, pred)
+}
+
+
+/// The index of the first element that is not less than `x`: the first position where
+/// `x` could be inserted while keeping `values` sorted.
+pub fn lower_bound<T: PartialOrd>(values: &[T], x: &T) -> usize {
+    sequence::lower_bound(values, x)
+}
+
+
+/// The index of the first element that is greater than `x`: the last position where `x`
+/// could be inserted while keeping `values` sorted.
+pub fn upper_bound<T: PartialOrd>(values: &[T], x: &T) -> usize {
+    sequence::upper_bound(values, x)
+}
+
+
+/// The range of indices holding values equal to `x`. When there are none, the range is
+/// empty and starts at the insertion point for `x`.
+pub fn equal_range<T: PartialOrd>(values: &[T], x: &T) -> Range<usize> {
+    sequence::equal_range(values, x)
+}
+
+
+/// The index of the first element equal to `x`.
+pub fn first_occurrence<T: PartialOrd>(values: &[T], x: &T) -> Option<usize> {
+    sequence::first_occurrence(values, x)
+}
+
+
+/// The index of the last element equal to `x`.
+pub fn last_occurrence<T: PartialOrd>(values: &[T], x: &T) -> Option<usize> {
+    sequence::last_occurrence(values, x)
+}
+
+
+/// The indices of the values whose key, given by `key`, falls within `range`, where
+/// `values` is sorted by that key. An empty (or backwards) range gives an empty range of
+/// indices.
+pub fn range_by_key<T, K, F, R>(values: &[T], key: F, range: R) -> Range<usize>
+where
+    K: PartialOrd + ?Sized,
+    F: Fn(&T) -> &K,
+    R: RangeBounds<K>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(s) => partition_point(values, |v| key(v) < s),
+        Bound::Excluded(s) => partition_point(values, |v| key(v) <= s),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(e) => partition_point(values, |v| key(v) <= e),
+        Bound::Excluded(e) => partition_point(values, |v| key(v) < e),
+        Bound::Unbounded => values.len(),
+    };
+    start..end.max(start)
+}
+
+
+/// Check that `values` is sorted in ascending order. This is O(n), so it's meant for
+/// validating input in debug builds or at trust boundaries, not for every search.
+pub fn check_sorted<T: PartialOrd>(values: &[T]) -> Result<(), Error> {
+    sequence::check_sorted(values)
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn partition_point() {
+        let data = vec![1_i32, 3, 4, 5, 7, 10, 15, 19];
+        let cases: Vec<i32> = vec![0, 1, 6, 19, 20];
+        let expected: Vec<usize> = vec![0, 0, 4, 7, 8];
+        for (x, expect) in cases.iter().zip(&expected) {
+            assert_eq!(super::partition_point(&data, |v| v < x), *expect);
+        }
+        let empty: Vec<i32> = vec![];
+        assert_eq!(super::partition_point(&empty, |v| *v < 3), 0);
+    }
+
+    #[test]
+    fn bounds() {
+        let data = vec![1_i32, 2, 2, 2, 3, 5, 5, 8];
+        // (x, lower, upper, first, last)
+        let cases = vec![
+            (0, 0, 0, None, None),
+            (1, 0, 1, Some(0), Some(0)),
+            (2, 1, 4, Some(1), Some(3)),
+            (4, 5, 5, None, None),
+            (5, 5, 7, Some(5), Some(6)),
+            (8, 7, 8, Some(7), Some(7)),
+            (9, 8, 8, None, None),
+        ];
+        for (x, lower, upper, first, last) in cases {
+            assert_eq!(super::lower_bound(&data, &x), lower);
+            assert_eq!(super::upper_bound(&data, &x), upper);
+            assert_eq!(super::equal_range(&data, &x), lower..upper);
+            assert_eq!(super::first_occurrence(&data, &x), first);
+            assert_eq!(super::last_occurrence(&data, &x), last);
+        }
+    }
+
+    #[test]
+    fn empty_and_all_equal() {
+        let empty: Vec<i32> = vec![];
+        assert_eq!(super::equal_range(&empty, &1), 0..0);
+        assert_eq!(super::first_occurrence(&empty, &1), None);
+        assert_eq!(super::last_occurrence(&empty, &1), None);
+        let same = vec![4; 1000];
+        assert_eq!(super::equal_range(&same, &4), 0..1000);
+        assert_eq!(super::equal_range(&same, &3), 0..0);
+        assert_eq!(super::equal_range(&same, &5), 1000..1000);
+        assert_eq!(super::first_occurrence(&same, &4), Some(0));
+        assert_eq!(super::last_occurrence(&same, &4), Some(999));
+    }
+
+    #[test]
+    fn matches_linear_scan() {
+        let data: Vec<i32> = (0..500).map(|i| i / 3 * 2).collect();
+        for x in -1..340 {
+            let lower = data.iter().take_while(|v| **v < x).count();
+            let upper = data.iter().take_while(|v| **v <= x).count();
+            assert_eq!(super::equal_range(&data, &x), lower..upper);
+        }
+    }
+
+    #[test]
+    fn range_by_key() {
+        let data = vec![1_i32, 2, 2, 2, 3, 5, 5, 8];
+        fn key(v: &i32) -> &i32 { v }
+        assert_eq!(super::range_by_key(&data, key, 2..5), 1..5);
+        assert_eq!(super::range_by_key(&data, key, 2..=5), 1..7);
+        assert_eq!(super::range_by_key(&data, key, ..3), 0..4);
+        assert_eq!(super::range_by_key(&data, key, 4..), 5..8);
+        assert_eq!(super::range_by_key(&data, key, ..), 0..8);
+        assert_eq!(super::range_by_key(&data, key, 6..7), 7..7);
+        let pairs = vec![(1, 'a'), (3, 'b'), (3, 'c'), (7, 'd')];
+        assert_eq!(super::range_by_key(&pairs, |p| &p.0, 2..=3), 1..3);
+    }
+
+    #[test]
+    fn check_sorted() {
+        use crate::error::Error;
+        assert_eq!(super::check_sorted::<i32>(&[]), Ok(()));
+        assert_eq!(super::check_sorted(&[1, 2, 2, 3]), Ok(()));
+        assert_eq!(super::check_sorted(&[1, 3, 2]), Err(Error::Unsorted { index: 2 }));
+        assert_eq!(super::check_sorted(&[1., f64::NAN]), Err(Error::Incomparable));
+    }
+}
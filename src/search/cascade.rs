@@ -0,0 +1,165 @@
+//! Fractional cascading: searching for the same key in each of `k` sorted lists in
+//! O(log n + k) time, rather than the O(k log n) of a separate binary search per list.
+//!
+//! Every list is augmented with every other element of the augmented list after it:
+//! `M[k-1] = L[k-1]`, and `M[i] = merge(L[i], M[i+1][1], M[i+1][3], ...)`. Each position
+//! of `M[i]` also records how many elements of `L[i]` come before it, and how many of
+//! the promoted elements of `M[i+1]` do. Only `M[0]` is binary searched; from the lower
+//! bound in `M[i]`, the lower bound in `L[i]` is read off directly, and the lower bound
+//! in `M[i+1]` is one of two neighbouring positions, since `M[i]` holds every other one
+//! of its elements. The augmented lists take at most twice as much space as the input.
+
+use crate::search::bounds;
+
+
+struct Level<T> {
+    keys: Vec<T>,
+    /// `own[p]`: the number of elements of this level's own list in `keys[..p]`.
+    own: Vec<usize>,
+    /// `promoted[p]`: the number of elements promoted from the next level in `keys[..p]`.
+    promoted: Vec<usize>,
+}
+
+
+pub struct FractionalCascade<T> {
+    lists: Vec<Vec<T>>,
+    levels: Vec<Level<T>>,
+}
+
+
+impl<T: PartialOrd + Clone> FractionalCascade<T> {
+    /// Preprocess the sorted `lists` in O(total length).
+    pub fn new(lists: Vec<Vec<T>>) -> Self {
+        let mut levels: Vec<Level<T>> = Vec::with_capacity(lists.len());
+        for list in lists.iter().rev() {
+            let promoted: Vec<&T> = match levels.last() {
+                Some(next) => next.keys.iter().skip(1).step_by(2).collect(),
+                None => vec![],
+            };
+            levels.push(merge_level(list, &promoted));
+        }
+        levels.reverse();
+        Self { lists, levels }
+    }
+
+    pub fn lists(&self) -> &[Vec<T>] {
+        &self.lists
+    }
+
+    /// For each list, the index of the first element that is not less than `x`.
+    pub fn lower_bounds(&self, x: &T) -> Vec<usize> {
+        let mut out = Vec::with_capacity(self.levels.len());
+        let first = match self.levels.first() {
+            Some(first) => first,
+            None => return out,
+        };
+        let mut p = bounds::lower_bound(&first.keys, x);
+        for (i, level) in self.levels.iter().enumerate() {
+            out.push(level.own[p]);
+            if let Some(next) = self.levels.get(i + 1) {
+                // the `c` promoted elements before `p` are `next.keys[1], [3], .., [2c-1]`,
+                // all less than `x`, while `next.keys[2c+1]` (if any) is not
+                let mut q = 2 * level.promoted[p];
+                if q < next.keys.len() && next.keys[q] < *x {
+                    q += 1;
+                }
+                p = q;
+            }
+        }
+        out
+    }
+
+    /// For each list, the index of the first occurrence of `x`.
+    pub fn search(&self, x: &T) -> Vec<Option<usize>> {
+        self.lower_bounds(x)
+            .into_iter()
+            .zip(&self.lists)
+            .map(|(idx, list)| match list.get(idx) {
+                Some(v) if v == x => Some(idx),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+
+/// Merge a level's own list with the elements promoted from the next level, recording
+/// the prefix counts of each.
+fn merge_level<T: PartialOrd + Clone>(list: &[T], promoted: &[&T]) -> Level<T> {
+    let n = list.len() + promoted.len();
+    let mut keys = Vec::with_capacity(n);
+    let mut own = Vec::with_capacity(n + 1);
+    let mut up = Vec::with_capacity(n + 1);
+    let (mut a, mut b) = (0, 0);
+    while a < list.len() || b < promoted.len() {
+        own.push(a);
+        up.push(b);
+        let take_own = match (list.get(a), promoted.get(b)) {
+            (Some(x), Some(y)) => x <= *y,
+            (Some(_), None) => true,
+            _ => false,
+        };
+        if take_own {
+            keys.push(list[a].clone());
+            a += 1;
+        } else {
+            keys.push(promoted[b].clone());
+            b += 1;
+        }
+    }
+    own.push(a);
+    up.push(b);
+    Level { keys, own, promoted: up }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::search::bounds;
+    use super::FractionalCascade;
+
+    #[test]
+    fn search() {
+        let lists = vec![
+            vec![1, 3, 5, 7],
+            vec![2, 3, 3, 8],
+            vec![],
+            vec![0, 4, 9],
+        ];
+        let cascade = FractionalCascade::new(lists);
+        assert_eq!(cascade.lower_bounds(&3), vec![1, 1, 0, 1]);
+        assert_eq!(cascade.search(&3), vec![Some(1), Some(1), None, None]);
+        assert_eq!(cascade.lower_bounds(&10), vec![4, 4, 0, 3]);
+        assert_eq!(cascade.lower_bounds(&-1), vec![0, 0, 0, 0]);
+        let empty = FractionalCascade::<i32>::new(vec![]);
+        assert!(empty.lower_bounds(&1).is_empty());
+    }
+
+    #[test]
+    fn matches_binary_search() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let lists: Vec<Vec<i32>> = (0..30)
+            .map(|_| {
+                let len = rng.gen_range(0..200);
+                let mut list: Vec<i32> = (0..len).map(|_| rng.gen_range(0..500)).collect();
+                list.sort();
+                list
+            })
+            .collect();
+        let cascade = FractionalCascade::new(lists.clone());
+        for x in -1..=501 {
+            let expected: Vec<usize> = lists
+                .iter()
+                .map(|list| bounds::lower_bound(list, &x))
+                .collect();
+            assert_eq!(cascade.lower_bounds(&x), expected, "x = {x}");
+            let expected: Vec<Option<usize>> = lists
+                .iter()
+                .map(|list| bounds::first_occurrence(list, &x))
+                .collect();
+            assert_eq!(cascade.search(&x), expected, "x = {x}");
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Exponential (galloping) search: probe indices 1, 2, 4, 8, ... until passing `x`, then
+//! binary search the last gap. This costs O(log i) comparisons, where `i` is the position
+//! of `x`, rather than O(log n), so it wins when the target is near the front of a very
+//! large sequence, and it works on sequences whose length isn't known at all.
+
+
+/// Search a sorted sequence given only as a function from index to value, such as a
+/// lazily evaluated or unbounded one. `at` returns `None` past the end of the sequence,
+/// and is only called on O(log i) indices.
+/// Returns the index of the first occurrence of `x`, and the number of comparisons made.
+pub fn exponential_search_fn<T, F>(mut at: F, x: &T) -> (Option<usize>, usize)
+where
+    T: PartialOrd,
+    F: FnMut(usize) -> Option<T>,
+{
+    let mut comparisons = 0;
+    // is there a value at `i`, and is it less than `x`?
+    let mut before = |i: usize, comparisons: &mut usize| match at(i) {
+        Some(v) => {
+            *comparisons += 1;
+            v < *x
+        },
+        None => false,
+    };
+    // gallop until `before(hi)` is false; the first index that isn't before `x` is then
+    // somewhere in `lo..=hi`
+    let (mut lo, mut hi) = (0, 0);
+    while before(hi, &mut comparisons) {
+        if hi == usize::MAX {
+            // there's no index past `hi` to gallop to; the search below then ends at
+            // `hi`, which is before `x`, so `x` isn't found
+            break
+        }
+        lo = hi + 1;
+        hi = match hi.checked_mul(2) {
+            Some(next) => next + 1,
+            None => usize::MAX,
+        };
+    }
+    // binary search for the first index in `lo..hi` that isn't before `x`
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if before(mid, &mut comparisons) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    let found = match at(lo) {
+        Some(v) => {
+            comparisons += 1;
+            v == *x
+        },
+        None => false,
+    };
+    (found.then_some(lo), comparisons)
+}
+
+
+/// Return the index of the first occurrence of `x` in the sorted `values`.
+pub fn exponential_search<T: PartialOrd>(values: &[T], x: &T) -> Option<usize> {
+    exponential_search_counted(values, x).0
+}
+
+
+/// Same as `exponential_search()`, but also returns the number of comparisons made.
+pub fn exponential_search_counted<T: PartialOrd>(values: &[T], x: &T) -> (Option<usize>, usize) {
+    exponential_search_fn(|i| values.get(i), &x)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::search::bounds;
+
+    #[test]
+    fn exponential_search() {
+        let data = vec![1_i32, 3, 4, 5, 7, 10, 15, 19];
+        let test_cases = vec![
+            (1, Some(0)),
+            (3, Some(1)),
+            (15, Some(6)),
+            (19, Some(7)),
+            (13, None),
+            (0, None),
+            (20, None),
+        ];
+        for (value, expected) in test_cases {
+            let result = super::exponential_search(&data, &value);
+            assert_eq!(result, expected);
+        }
+        let empty: Vec<i32> = vec![];
+        assert_eq!(super::exponential_search(&empty, &1), None);
+        let dups = vec![1, 2, 2, 2, 2, 2, 2, 2, 2, 3];
+        assert_eq!(super::exponential_search(&dups, &2), Some(1));
+    }
+
+    #[test]
+    fn matches_bounds() {
+        let data: Vec<i32> = (0..777).map(|i| i / 2 * 3).collect();
+        for x in -2..1200 {
+            assert_eq!(super::exponential_search(&data, &x), bounds::first_occurrence(&data, &x));
+        }
+    }
+
+    #[test]
+    fn unbounded() {
+        // the squares, never materialized and without an end
+        let squares = |i: usize| Some(i * i);
+        assert_eq!(super::exponential_search_fn(squares, &(1234 * 1234)).0, Some(1234));
+        assert_eq!(super::exponential_search_fn(squares, &(1234 * 1234 + 1)).0, None);
+        // a lazy sequence with an end that is only discovered by probing
+        let lazy = |i: usize| (i < 100).then_some(2 * i);
+        assert_eq!(super::exponential_search_fn(lazy, &198).0, Some(99));
+        assert_eq!(super::exponential_search_fn(lazy, &200).0, None);
+    }
+
+    #[test]
+    fn fewer_comparisons_near_front() {
+        let data: Vec<u64> = (0..1_000_000).collect();
+        let x = 5;
+        let mut binary = 0;
+        bounds::partition_point(&data, |v| {
+            binary += 1;
+            *v < x
+        });
+        let (result, exponential) = super::exponential_search_counted(&data, &x);
+        assert_eq!(result, Some(5));
+        assert!(exponential < binary / 2, "{exponential} vs {binary}");
+        // but it takes about twice as many comparisons near the back
+        let x = 999_998;
+        let mut binary = 0;
+        bounds::partition_point(&data, |v| {
+            binary += 1;
+            *v < x
+        });
+        let (_, exponential) = super::exponential_search_counted(&data, &x);
+        assert!(exponential > binary, "{exponential} vs {binary}");
+    }
+
+    #[test]
+    fn never_reaches_x() {
+        // an unbounded sequence whose values all stay below `x`
+        let (found, comparisons) = super::exponential_search_fn(|_| Some(0_u8), &1);
+        assert_eq!(found, None);
+        assert!(comparisons <= 2 * usize::BITS as usize + 2, "{comparisons}");
+        // the last index can still be found
+        let (found, _) = super::exponential_search_fn(|i| Some(i / 2), &(usize::MAX / 2));
+        assert_eq!(found, Some(usize::MAX - 1));
+    }
+}
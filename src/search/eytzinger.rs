@@ -0,0 +1,143 @@
+//! Binary search over the Eytzinger (BFS) layout of a sorted array: the array is stored
+//! as an implicit complete binary search tree, in the same order as a binary heap, so
+//! the children of node `k` are at `2k` and `2k + 1` (1-indexed). The first few levels
+//! of every search share the same few cache lines, and the nodes a search could visit a
+//! few levels down are contiguous, which lets them be prefetched while the current
+//! comparison is still in flight. A plain binary search, by contrast, lands each of its
+//! first probes on a different cache line.
+
+use crate::search::prefetch::prefetch;
+
+
+pub struct Eytzinger<T> {
+    /// 1-indexed tree; `keys[0]` is only padding, so it holds a copy of the first key.
+    keys: Vec<T>,
+    /// For each node, the index of its key in the original sorted slice.
+    indices: Vec<usize>,
+}
+
+
+impl<T: PartialOrd + Clone> Eytzinger<T> {
+    /// Rearrange the sorted slice `values` into Eytzinger order.
+    pub fn new(values: &[T]) -> Self {
+        let n = values.len();
+        let mut keys = Vec::with_capacity(n + 1);
+        let mut indices = vec![0; n + 1];
+        if let Some(first) = values.first() {
+            keys.resize(n + 1, first.clone());
+        }
+        let mut next = 0;
+        fill(values, &mut keys, &mut indices, &mut next, 1);
+        Self { keys, indices }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The node holding the first key that is not less than `x`, or 0 if there is none.
+    fn lower_bound_node(&self, x: &T) -> usize {
+        let n = self.len();
+        let base = self.keys.as_ptr();
+        let mut k = 1;
+        while k <= n {
+            // the 16 nodes four levels below `k` are contiguous, starting at `16k`
+            prefetch(base.wrapping_add(16 * k));
+            // branchless: go right exactly when the key is less than `x`
+            k = 2 * k + (self.keys[k] < *x) as usize;
+        }
+        // `k` fell off the tree; the answer is the last node where the search went left,
+        // which is found by undoing every right turn (trailing ones) and one left turn
+        k >> (k.trailing_ones() + 1)
+    }
+
+    /// The index, in the original sorted slice, of the first value that is not less than
+    /// `x`, or the length of the slice if there is none.
+    pub fn lower_bound(&self, x: &T) -> usize {
+        match self.lower_bound_node(x) {
+            0 => self.len(),
+            k => self.indices[k],
+        }
+    }
+
+    /// The index, in the original sorted slice, of the first occurrence of `x`. This
+    /// gives the same answers as `binary_search::first::binary_search()`.
+    pub fn search(&self, x: &T) -> Option<usize> {
+        match self.lower_bound_node(x) {
+            0 => None,
+            k if self.keys[k] == *x => Some(self.indices[k]),
+            _ => None,
+        }
+    }
+}
+
+
+/// In-order traversal of the implicit tree, handing out the sorted values in order.
+fn fill<T: Clone>(
+    values: &[T],
+    keys: &mut [T],
+    indices: &mut [usize],
+    next: &mut usize,
+    k: usize,
+) {
+    if k > values.len() {
+        return
+    }
+    fill(values, keys, indices, next, 2 * k);
+    keys[k] = values[*next].clone();
+    indices[k] = *next;
+    *next += 1;
+    fill(values, keys, indices, next, 2 * k + 1);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::binary_search;
+    use super::Eytzinger;
+
+    #[test]
+    fn layout() {
+        let tree = Eytzinger::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(&tree.keys[1..], &[6, 3, 8, 1, 5, 7, 9, 0, 2, 4]);
+        assert_eq!(&tree.indices[1..], &[6, 3, 8, 1, 5, 7, 9, 0, 2, 4]);
+    }
+
+    #[test]
+    fn search() {
+        let data = vec![1_i32, 3, 4, 5, 7, 10, 15, 19];
+        let tree = Eytzinger::new(&data);
+        let test_cases = vec![
+            (1, Some(0)),
+            (3, Some(1)),
+            (15, Some(6)),
+            (19, Some(7)),
+            (13, None),
+            (0, None),
+            (20, None),
+        ];
+        for (value, expected) in test_cases {
+            assert_eq!(tree.search(&value), expected);
+        }
+        let empty = Eytzinger::<i32>::new(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.search(&1), None);
+        assert_eq!(empty.lower_bound(&1), 0);
+    }
+
+    #[test]
+    fn matches_binary_search() {
+        for n in [1, 2, 3, 7, 8, 9, 31, 100, 1000] {
+            let data: Vec<i32> = (0..n).map(|i| i / 3 * 2).collect();
+            let tree = Eytzinger::new(&data);
+            for x in -1..=(n / 3 * 2 + 1) {
+                assert_eq!(tree.search(&x), binary_search::first::binary_search(&data, &x));
+                assert_eq!(tree.lower_bound(&x), data.partition_point(|v| *v < x));
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Interpolation search: instead of always probing the middle, guess where `x` should be
+//! by assuming the values grow linearly between the ends of the current range. On
+//! uniformly distributed keys this takes O(log log n) probes on average, but it degrades
+//! to O(n) on skewed distributions (e.g. exponentially growing keys), where a plain
+//! binary search is the safer choice.
+
+use crate::search::bounds;
+
+
+/// Numeric keys that can be interpolated between.
+pub trait Numeric: PartialOrd + Copy {
+    fn to_f64(self) -> f64;
+}
+
+
+macro_rules! impl_numeric {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+
+impl_numeric!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+
+/// Return the index of the first occurrence of `x` in the sorted `values`.
+pub fn interpolation_search<T: Numeric>(values: &[T], x: T) -> Option<usize> {
+    interpolation_search_counted(values, x).0
+}
+
+
+/// Same as `interpolation_search()`, but also returns the number of comparisons made.
+pub fn interpolation_search_counted<T: Numeric>(values: &[T], x: T) -> (Option<usize>, usize) {
+    let mut comparisons = 0;
+    // an `x` that can't be compared (`NaN`) can't be equal to anything
+    if values.is_empty() || x.partial_cmp(&x).is_none() {
+        return (None, comparisons)
+    }
+    let (mut lo, mut hi) = (0, values.len() - 1);
+    loop {
+        // `x` can only be in `lo..=hi` if it is within the values at the ends
+        comparisons += 2;
+        if x < values[lo] || x > values[hi] {
+            return (None, comparisons)
+        }
+        let (v_lo, v_hi) = (values[lo].to_f64(), values[hi].to_f64());
+        let pos = if v_hi > v_lo {
+            let fraction = (x.to_f64() - v_lo) / (v_hi - v_lo);
+            // clamped, in case of rounding
+            (lo + (fraction * (hi - lo) as f64) as usize).min(hi)
+        } else {
+            // every value in the range is equal to `x`
+            lo
+        };
+        comparisons += 1;
+        if values[pos] < x {
+            lo = pos + 1;
+            continue
+        }
+        comparisons += 1;
+        if values[pos] > x {
+            // `pos > lo` here, since `values[lo] <= x`
+            hi = pos - 1;
+            continue
+        }
+        // neither less nor greater, but `values[pos]` may still not be comparable to `x`
+        comparisons += 1;
+        if values[pos] != x {
+            return (None, comparisons)
+        }
+        // found; any earlier occurrences are in `lo..pos`, but usually there are none
+        comparisons += 1;
+        if pos == lo || values[pos-1] < x {
+            return (Some(pos), comparisons)
+        }
+        let first = lo + bounds::partition_point(&values[lo..pos], |v| {
+            comparisons += 1;
+            *v < x
+        });
+        return (Some(first), comparisons)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::search::bounds;
+
+    #[test]
+    fn interpolation_search() {
+        let data = vec![1_i32, 3, 4, 5, 7, 10, 15, 19];
+        let test_cases = vec![
+            (1, Some(0)),
+            (3, Some(1)),
+            (15, Some(6)),
+            (19, Some(7)),
+            (13, None),
+            (0, None),
+            (20, None),
+        ];
+        for (value, expected) in test_cases {
+            let result = super::interpolation_search(&data, value);
+            assert_eq!(result, expected);
+        }
+        let empty: Vec<i32> = vec![];
+        assert_eq!(super::interpolation_search(&empty, 1), None);
+        let same = vec![2.5_f64; 10];
+        assert_eq!(super::interpolation_search(&same, 2.5), Some(0));
+    }
+
+    #[test]
+    fn matches_bounds() {
+        let data: Vec<i64> = (0..777).map(|i| i / 2 * 3 + (i % 5) * 100).collect();
+        let mut data = data;
+        data.sort();
+        for x in -2..2000 {
+            assert_eq!(
+                super::interpolation_search(&data, x),
+                bounds::first_occurrence(&data, &x),
+            );
+        }
+    }
+
+    #[test]
+    fn fewer_comparisons_when_uniform() {
+        let data: Vec<u64> = (0..1_000_000).map(|i| i * 3).collect();
+        for x in [3, 1_234_566, 2_999_997] {
+            let mut binary = 0;
+            bounds::partition_point(&data, |v| {
+                binary += 1;
+                *v < x
+            });
+            let (result, interpolation) = super::interpolation_search_counted(&data, x);
+            assert_eq!(result, Some((x / 3) as usize));
+            assert!(interpolation < binary / 2, "{interpolation} vs {binary}");
+        }
+    }
+
+    #[test]
+    fn more_comparisons_when_skewed() {
+        // most of the range is taken up by a handful of huge values
+        let mut data: Vec<u64> = (0..10_000).collect();
+        data.extend((0..10).map(|i| 1 << (40 + i)));
+        let x = 9_999;
+        let mut binary = 0;
+        bounds::partition_point(&data, |v| {
+            binary += 1;
+            *v < x
+        });
+        let (result, interpolation) = super::interpolation_search_counted(&data, x);
+        assert_eq!(result, Some(9_999));
+        assert!(interpolation > binary, "{interpolation} vs {binary}");
+    }
+
+    #[test]
+    fn incomparable() {
+        assert_eq!(super::interpolation_search(&[1.0, 2.0, 3.0], f64::NAN), None);
+        assert_eq!(super::interpolation_search(&[f64::NAN], f64::NAN), None);
+        // a `NaN` where `x` would be is not a match
+        assert_eq!(super::interpolation_search(&[1.0, f64::NAN, 3.0], 2.0), None);
+        assert_eq!(super::interpolation_search(&[1.0, 2.0, 3.0], 2.0), Some(1));
+    }
+}
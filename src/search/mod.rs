@@ -0,0 +1,15 @@
+//! Searching sorted sequences. Where `binary_search` is for playing around with
+//! implementations, this module is meant to be correct and total: every function returns
+//! a well-defined result for empty slices, duplicates, and values that aren't present.
+
+pub mod bisection;
+pub mod bitonic;
+pub mod bounds;
+pub mod cascade;
+pub mod exponential;
+pub mod eytzinger;
+pub mod interpolation;
+pub mod prefetch;
+pub mod rotated;
+pub mod sequence;
+pub mod static_btree;
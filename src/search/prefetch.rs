@@ -0,0 +1,16 @@
+//! Software prefetch: a hint to start loading a cache line that will be needed soon.
+
+/// Ask the CPU to pull the cache line holding `ptr` into all levels of cache.
+/// Prefetching is only a hint: it never faults, so `ptr` may be dangling or out of
+/// bounds. On architectures other than x86_64 this does nothing.
+#[inline(always)]
+pub fn prefetch<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: prefetch never dereferences the pointer in a way that can fault
+    unsafe {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch::<_MM_HINT_T0>(ptr.cast());
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = ptr;
+}
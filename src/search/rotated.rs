@@ -0,0 +1,106 @@
+//! Searching a sorted array that has been rotated, e.g. a snapshot of a ring buffer that
+//! was filled in order: `[5, 6, 7, 1, 2, 3, 4]`. First find the rotation point (where
+//! the smallest values start), and then binary search each of the two sorted halves.
+
+use crate::search::bounds;
+
+
+/// The index `k` where the original sorted order starts, so that `values[k..]` followed
+/// by `values[..k]` is sorted. Returns 0 if `values` isn't rotated at all.
+/// This is a binary search for the first value that is less than `values[0]`. With
+/// duplicates, values at the end that equal `values[0]` have to be skipped first, which
+/// makes the worst case O(n) (e.g. `[1, 1, 1, 0, 1, 1]`), since then the two halves
+/// can't be told apart without looking at every value.
+pub fn rotation_point<T: Ord>(values: &[T]) -> usize {
+    let first = match values.first() {
+        Some(first) => first,
+        None => return 0,
+    };
+    let mut end = values.len();
+    while end > 1 && values[end-1] == *first {
+        end -= 1;
+    }
+    if end == 1 {
+        // every value is the same
+        return 0
+    }
+    let k = bounds::partition_point(&values[..end], |v| v >= first);
+    if k == values.len() { 0 } else { k }
+}
+
+
+/// Return the index of the first occurrence of `x`, in sorted order, in the rotated
+/// sorted array `values`.
+pub fn search_rotated<T: Ord>(values: &[T], x: &T) -> Option<usize> {
+    let k = rotation_point(values);
+    match bounds::first_occurrence(&values[k..], x) {
+        Some(idx) => Some(k + idx),
+        None => bounds::first_occurrence(&values[..k], x),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn rotation_point() {
+        let cases: Vec<Vec<i32>> = vec![
+            vec![],
+            vec![1],
+            vec![1, 2, 3, 4],
+            vec![5, 6, 7, 1, 2, 3, 4],
+            vec![2, 1],
+            vec![2, 2, 2],
+            vec![1, 1, 1, 0, 1, 1],
+            vec![1, 0, 1, 1, 1],
+            vec![1, 2, 3, 1],
+            vec![3, 3, 1, 2, 3],
+        ];
+        let expected: Vec<usize> = vec![0, 0, 0, 3, 1, 0, 3, 1, 3, 2];
+        for (case, expect) in cases.iter().zip(&expected) {
+            let k = super::rotation_point(case);
+            assert_eq!(k, *expect, "{case:?}");
+            let mut unrotated = case[k..].to_vec();
+            unrotated.extend_from_slice(&case[..k]);
+            assert!(unrotated.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+
+    #[test]
+    fn search_rotated() {
+        let data = vec![15_i32, 19, 1, 3, 4, 5, 7, 10];
+        let test_cases = vec![
+            (1, Some(2)),
+            (3, Some(3)),
+            (10, Some(7)),
+            (15, Some(0)),
+            (19, Some(1)),
+            (13, None),
+            (0, None),
+            (20, None),
+        ];
+        for (value, expected) in test_cases {
+            assert_eq!(super::search_rotated(&data, &value), expected);
+        }
+    }
+
+    #[test]
+    fn every_rotation() {
+        let sorted: Vec<i32> = (0..40).map(|i| i / 3).collect();
+        for k in 0..sorted.len() {
+            let mut data = sorted.clone();
+            data.rotate_left(k);
+            for x in -1..15 {
+                let expected = data.iter().position(|v| *v == x);
+                let found = super::search_rotated(&data, &x);
+                // equal values may straddle the end of the array, so only the value can
+                // be compared, not the index
+                assert_eq!(found.is_some(), expected.is_some());
+                if let Some(idx) = found {
+                    assert_eq!(data[idx], x);
+                }
+            }
+        }
+    }
+}
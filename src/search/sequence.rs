@@ -0,0 +1,306 @@
+//! The searches in `search::bounds`, generalized from slices to any sorted sequence with
+//! random access, so nothing has to be copied into a slice first. This covers:
+//! - `VecDeque`, whose contents may wrap around the end of its buffer.
+//! - `FixedWidthRecords`: records of a fixed size in a byte buffer, such as a
+//!   memory-mapped file, sorted by a key decoded from each record.
+//! - `FnSequence`: virtual sequences, where each element is computed from its index.
+
+use std::{borrow::Borrow, cmp::Ordering, collections::VecDeque, marker::PhantomData, ops::Range};
+
+use crate::error::Error;
+
+
+/// A sequence with O(1) access by index. It's implemented for references to containers
+/// (so `Item` can borrow from them), and for views that produce their elements by value.
+pub trait RandomAccess {
+    type Item;
+
+    fn len(&self) -> usize;
+
+    /// The element at `idx`, where `idx < self.len()`.
+    fn at(&self, idx: usize) -> Self::Item;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+
+impl<'a, T> RandomAccess for &'a [T] {
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn at(&self, idx: usize) -> &'a T {
+        let values: &'a [T] = self;
+        &values[idx]
+    }
+}
+
+
+impl<'a, T> RandomAccess for &'a Vec<T> {
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn at(&self, idx: usize) -> &'a T {
+        let values: &'a Vec<T> = self;
+        &values[idx]
+    }
+}
+
+
+impl<'a, T> RandomAccess for &'a VecDeque<T> {
+    type Item = &'a T;
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn at(&self, idx: usize) -> &'a T {
+        let values: &'a VecDeque<T> = self;
+        &values[idx]
+    }
+}
+
+
+/// Records of `width` bytes each, laid out back to back in `bytes`, e.g. a memory-mapped
+/// file. `key` decodes the key that the records are sorted by. Any trailing bytes that
+/// don't make up a whole record are ignored.
+#[derive(Clone, Copy)]
+pub struct FixedWidthRecords<'a, K> {
+    bytes: &'a [u8],
+    width: usize,
+    key: fn(&[u8]) -> K,
+}
+
+
+impl<'a, K> FixedWidthRecords<'a, K> {
+    pub fn new(bytes: &'a [u8], width: usize, key: fn(&[u8]) -> K) -> Self {
+        assert!(width > 0, "records can't be empty");
+        Self { bytes, width, key }
+    }
+
+    /// The raw bytes of the record at `idx`.
+    pub fn record(&self, idx: usize) -> &'a [u8] {
+        &self.bytes[idx * self.width..(idx + 1) * self.width]
+    }
+}
+
+
+impl<K> RandomAccess for FixedWidthRecords<'_, K> {
+    type Item = K;
+
+    fn len(&self) -> usize {
+        self.bytes.len() / self.width
+    }
+
+    fn at(&self, idx: usize) -> K {
+        (self.key)(self.record(idx))
+    }
+}
+
+
+/// A sequence of `len` elements that only exist as a function of their index.
+pub struct FnSequence<T, F> {
+    len: usize,
+    f: F,
+    _item: PhantomData<fn() -> T>,
+}
+
+
+impl<T, F: Fn(usize) -> T> FnSequence<T, F> {
+    pub fn new(len: usize, f: F) -> Self {
+        Self { len, f, _item: PhantomData }
+    }
+}
+
+
+impl<T, F: Fn(usize) -> T> RandomAccess for &FnSequence<T, F> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn at(&self, idx: usize) -> T {
+        (self.f)(idx)
+    }
+}
+
+
+/// `partition_point()` restricted to `lo..hi`.
+fn partition_in<S, P>(seq: &S, mut lo: usize, mut hi: usize, mut pred: P) -> usize
+where
+    S: RandomAccess,
+    P: FnMut(S::Item) -> bool,
+{
+    // invariant: `pred` is true for everything before `lo`, and false from `hi` onwards
+    while lo < hi {
+        // can't overflow, unlike `(lo + hi) / 2`
+        let mid = lo + (hi - lo) / 2;
+        if pred(seq.at(mid)) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+
+/// See `bounds::partition_point()`.
+pub fn partition_point<S, P>(seq: S, pred: P) -> usize
+where
+    S: RandomAccess,
+    P: FnMut(S::Item) -> bool,
+{
+    partition_in(&seq, 0, seq.len(), pred)
+}
+
+
+/// See `bounds::lower_bound()`.
+pub fn lower_bound<S, T>(seq: S, x: &T) -> usize
+where
+    S: RandomAccess,
+    S::Item: Borrow<T>,
+    T: PartialOrd + ?Sized,
+{
+    partition_point(seq, |v| v.borrow() < x)
+}
+
+
+/// See `bounds::upper_bound()`.
+pub fn upper_bound<S, T>(seq: S, x: &T) -> usize
+where
+    S: RandomAccess,
+    S::Item: Borrow<T>,
+    T: PartialOrd + ?Sized,
+{
+    partition_point(seq, |v| v.borrow() <= x)
+}
+
+
+/// See `bounds::equal_range()`.
+pub fn equal_range<S, T>(seq: S, x: &T) -> Range<usize>
+where
+    S: RandomAccess,
+    S::Item: Borrow<T>,
+    T: PartialOrd + ?Sized,
+{
+    let lo = partition_in(&seq, 0, seq.len(), |v| v.borrow() < x);
+    // everything equal to `x` is at or after `lo`, so only search the rest
+    let hi = partition_in(&seq, lo, seq.len(), |v| v.borrow() <= x);
+    lo..hi
+}
+
+
+/// See `bounds::first_occurrence()`.
+pub fn first_occurrence<S, T>(seq: S, x: &T) -> Option<usize>
+where
+    S: RandomAccess,
+    S::Item: Borrow<T>,
+    T: PartialOrd + ?Sized,
+{
+    let idx = partition_in(&seq, 0, seq.len(), |v| v.borrow() < x);
+    if idx < seq.len() && seq.at(idx).borrow() == x {
+        return Some(idx)
+    }
+    None
+}
+
+
+/// See `bounds::last_occurrence()`.
+pub fn last_occurrence<S, T>(seq: S, x: &T) -> Option<usize>
+where
+    S: RandomAccess,
+    S::Item: Borrow<T>,
+    T: PartialOrd + ?Sized,
+{
+    let idx = partition_in(&seq, 0, seq.len(), |v| v.borrow() <= x).checked_sub(1)?;
+    if seq.at(idx).borrow() == x {
+        return Some(idx)
+    }
+    None
+}
+
+
+/// See `bounds::check_sorted()`.
+pub fn check_sorted<S>(seq: S) -> Result<(), Error>
+where
+    S: RandomAccess,
+    S::Item: PartialOrd,
+{
+    for i in 1..seq.len() {
+        match seq.at(i - 1).partial_cmp(&seq.at(i)) {
+            Some(Ordering::Greater) => return Err(Error::Unsorted { index: i }),
+            None => return Err(Error::Incomparable),
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use crate::binary_search;
+    use super::{FixedWidthRecords, FnSequence};
+
+    #[test]
+    fn vec_deque() {
+        // wrap the contents around the end of the buffer
+        let mut deque = VecDeque::with_capacity(8);
+        for x in [10, 7, 5, 4, 3] {
+            deque.push_front(x);
+        }
+        for x in [15, 19] {
+            deque.push_back(x);
+        }
+        deque.push_front(1);
+        assert!(!deque.as_slices().1.is_empty());
+        assert_eq!(super::lower_bound(&deque, &6), 4);
+        assert_eq!(super::equal_range(&deque, &7), 4..5);
+        assert_eq!(super::last_occurrence(&deque, &19), Some(7));
+        assert_eq!(binary_search::first::binary_search(&deque, &15), Some(6));
+        assert_eq!(binary_search::first::binary_search(&deque, &13), None);
+        assert_eq!(super::check_sorted(&deque), Ok(()));
+    }
+
+    #[test]
+    fn fixed_width_records() {
+        // 12-byte records: a big-endian u64 key and a u32 payload
+        let mut bytes = vec![];
+        for key in [3_u64, 8, 8, 21, 40] {
+            bytes.extend_from_slice(&key.to_be_bytes());
+            bytes.extend_from_slice(&((key * 100) as u32).to_be_bytes());
+        }
+        // a partial record at the end is ignored
+        bytes.extend_from_slice(&[0xff; 5]);
+        let key = |record: &[u8]| u64::from_be_bytes(record[..8].try_into().unwrap());
+        let records = FixedWidthRecords::new(&bytes, 12, key);
+        assert_eq!(super::first_occurrence(records, &8), Some(1));
+        assert_eq!(super::last_occurrence(records, &8), Some(2));
+        assert_eq!(super::first_occurrence(records, &9), None);
+        assert_eq!(super::upper_bound(records, &100), 5);
+        let idx = binary_search::first::binary_search(records, &21).unwrap();
+        assert_eq!(&records.record(idx)[8..], &2100_u32.to_be_bytes());
+    }
+
+    #[test]
+    fn fn_sequence() {
+        // the squares of 0..1_000_000, never materialized
+        let squares = FnSequence::new(1_000_000, |i| (i * i) as u64);
+        assert_eq!(super::first_occurrence(&squares, &(777 * 777)), Some(777));
+        assert_eq!(super::first_occurrence(&squares, &(777 * 777 + 1)), None);
+        assert_eq!(super::lower_bound(&squares, &50), 8);
+        assert_eq!(super::partition_point(&squares, |v| v < 1 << 30), 1 << 15);
+        assert_eq!(binary_search::first::binary_search(&squares, &144), Some(12));
+    }
+}
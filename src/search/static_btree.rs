@@ -0,0 +1,160 @@
+//! A static B+ tree: a sorted array with a stack of index levels on top, all stored in a
+//! single contiguous array. Every node holds `B` keys, so with `B = 16` and 4-byte keys
+//! a node is exactly one cache line, and a search touches one cache line per level
+//! (log_16 n of them, rather than log_2 n for a binary search).
+//! Within a node, the position to descend to is the number of keys less than `x`, which
+//! is counted without branching, so the compiler is free to vectorize it.
+//!
+//! Layout:
+//! - level 0 (the leaves): the sorted values, padded to a multiple of `B`.
+//! - level 1: the largest key of each leaf, padded to a multiple of `B`.
+//! - level 2, ...: the largest key of each node of the level below, until a single node
+//!   (the root) is left.
+//!
+//! The levels are stored root first, and padding repeats the largest value, so it can
+//! never be counted as less than a value that is actually in the tree.
+
+use crate::search::prefetch::prefetch;
+
+
+pub struct StaticBTree<T, const B: usize = 16> {
+    keys: Vec<T>,
+    /// Start of each level in `keys`, from the root down to the leaves.
+    offsets: Vec<usize>,
+    len: usize,
+}
+
+
+impl<T: PartialOrd + Clone, const B: usize> StaticBTree<T, B> {
+    /// Build the tree from the sorted slice `values`.
+    pub fn new(values: &[T]) -> Self {
+        assert!(B >= 2, "nodes need at least two keys");
+        let len = values.len();
+        let last = match values.last() {
+            Some(last) => last.clone(),
+            None => return Self { keys: vec![], offsets: vec![], len },
+        };
+        // build bottom-up, then reverse so the root comes first
+        let mut levels: Vec<Vec<T>> = vec![];
+        let mut level = values.to_vec();
+        loop {
+            level.resize(level.len().div_ceil(B) * B, last.clone());
+            let n_nodes = level.len() / B;
+            let parent: Vec<T> = level.chunks(B).map(|node| node[B-1].clone()).collect();
+            levels.push(level);
+            if n_nodes == 1 {
+                break
+            }
+            level = parent;
+        }
+        let mut keys = Vec::with_capacity(levels.iter().map(Vec::len).sum());
+        let mut offsets = Vec::with_capacity(levels.len());
+        for level in levels.into_iter().rev() {
+            offsets.push(keys.len());
+            keys.extend(level);
+        }
+        Self { keys, offsets, len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of keys in `node` that are less than `x`, without branching.
+    fn rank_in_node(node: &[T], x: &T) -> usize {
+        node.iter().map(|k| (*k < *x) as usize).sum()
+    }
+
+    /// The index, in the original sorted slice, of the first value that is not less than
+    /// `x`, or the length of the slice if there is none.
+    pub fn lower_bound(&self, x: &T) -> usize {
+        if self.is_empty() {
+            return 0
+        }
+        let mut node = 0;
+        for (depth, offset) in self.offsets.iter().enumerate() {
+            let start = offset + node * B;
+            let rank = Self::rank_in_node(&self.keys[start..start + B], x);
+            if rank == B {
+                // `x` is larger than every key in the tree
+                return self.len
+            }
+            node = node * B + rank;
+            if let Some(next) = self.offsets.get(depth + 1) {
+                prefetch(self.keys.as_ptr().wrapping_add(next + node * B));
+            }
+        }
+        // at the leaves, `node` is the index into the padded, sorted values
+        node.min(self.len)
+    }
+
+    /// The index, in the original sorted slice, of the first occurrence of `x`. This
+    /// gives the same answers as `binary_search::first::binary_search()`.
+    pub fn search(&self, x: &T) -> Option<usize> {
+        let idx = self.lower_bound(x);
+        let leaves = self.offsets.last()?;
+        match self.keys.get(leaves + idx) {
+            Some(k) if idx < self.len && *k == *x => Some(idx),
+            _ => None,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::binary_search;
+    use super::StaticBTree;
+
+    #[test]
+    fn layout() {
+        let data: Vec<i32> = (0..10).collect();
+        let tree = StaticBTree::<i32, 4>::new(&data);
+        let expected = vec![
+            // root
+            3, 7, 9, 9,
+            // leaves
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 9, 9,
+        ];
+        assert_eq!(tree.keys, expected);
+        assert_eq!(tree.offsets, vec![0, 4]);
+    }
+
+    #[test]
+    fn search() {
+        let data = vec![1_i32, 3, 4, 5, 7, 10, 15, 19];
+        let tree = StaticBTree::<i32>::new(&data);
+        let test_cases = vec![
+            (1, Some(0)),
+            (3, Some(1)),
+            (15, Some(6)),
+            (19, Some(7)),
+            (13, None),
+            (0, None),
+            (20, None),
+        ];
+        for (value, expected) in test_cases {
+            assert_eq!(tree.search(&value), expected);
+        }
+        let empty = StaticBTree::<i32>::new(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.search(&1), None);
+        assert_eq!(empty.lower_bound(&1), 0);
+    }
+
+    #[test]
+    fn matches_binary_search() {
+        for n in [1, 2, 3, 4, 5, 15, 16, 17, 64, 65, 100, 1000] {
+            let data: Vec<i32> = (0..n).map(|i| i / 3 * 2).collect();
+            let tree = StaticBTree::<i32, 4>::new(&data);
+            for x in -1..=(n / 3 * 2 + 1) {
+                assert_eq!(tree.search(&x), binary_search::first::binary_search(&data, &x));
+                assert_eq!(tree.lower_bound(&x), data.partition_point(|v| *v < x));
+            }
+        }
+    }
+}
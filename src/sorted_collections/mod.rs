@@ -0,0 +1,16 @@
+//! Ordered collections backed by a sorted `Vec`, using the binary searches in
+//! `search::bounds`. Compared to a balanced tree, lookups and iteration are faster and
+//! more cache friendly, and rank/select are O(log n) and O(1), but inserting or removing
+//! is O(n), since everything after the position has to shift. They suit data that is
+//! built in bulk and queried much more often than it is modified.
+
+pub mod sorted_map;
+pub mod sorted_vec;
+
+
+/// Whether a collection may hold several equal values (or, for a map, keys).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplicates {
+    Allow,
+    Reject,
+}
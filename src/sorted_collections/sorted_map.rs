@@ -0,0 +1,150 @@
+//! A map (or multimap) from keys to values, kept as `(key, value)` pairs in a `Vec`
+//! sorted by key.
+
+use std::ops::RangeBounds;
+
+use crate::search::bounds;
+use crate::sorting::merge;
+use super::Duplicates;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortedMap<K, V> {
+    entries: Vec<(K, V)>,
+    duplicates: Duplicates,
+}
+
+
+impl<K: Ord + Clone, V: Clone> SortedMap<K, V> {
+    pub fn new(duplicates: Duplicates) -> Self {
+        Self { entries: vec![], duplicates }
+    }
+
+    /// Build from entries in any order, in O(n log n), with the stable merge sort, so
+    /// entries with equal keys keep their order. When duplicates are rejected, only the
+    /// first entry for each key is kept.
+    pub fn from_unsorted(entries: &[(K, V)], duplicates: Duplicates) -> Self {
+        let mut entries = merge::second::sort_by(entries, |a, b| a.0.cmp(&b.0));
+        if duplicates == Duplicates::Reject {
+            entries.dedup_by(|later, earlier| later.0 == earlier.0);
+        }
+        Self { entries, duplicates }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, (K, V)> {
+        self.entries.iter()
+    }
+
+    fn first_idx(&self, key: &K) -> Option<usize> {
+        let idx = bounds::partition_point(&self.entries, |(k, _)| k < key);
+        match self.entries.get(idx) {
+            Some((k, _)) if k == key => Some(idx),
+            _ => None,
+        }
+    }
+
+    /// Insert the entry after any with an equal key, and return whether it was inserted:
+    /// it isn't, if the key is already present and duplicates are rejected.
+    pub fn insert(&mut self, key: K, value: V) -> bool {
+        let idx = bounds::partition_point(&self.entries, |(k, _)| *k <= key);
+        if self.duplicates == Duplicates::Reject && idx > 0 && self.entries[idx-1].0 == key {
+            return false
+        }
+        self.entries.insert(idx, (key, value));
+        true
+    }
+
+    /// Remove the first entry for `key`, returning its value if there was one.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let idx = self.first_idx(key)?;
+        Some(self.entries.remove(idx).1)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.first_idx(key).is_some()
+    }
+
+    /// The value of the first entry for `key`.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.first_idx(key).map(|idx| &self.entries[idx].1)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.first_idx(key).map(|idx| &mut self.entries[idx].1)
+    }
+
+    /// All entries for `key`, in insertion order.
+    pub fn get_all(&self, key: &K) -> &[(K, V)] {
+        self.range(key..=key)
+    }
+
+    /// All entries with a key within `range`, in order.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> &[(K, V)] {
+        let idx = bounds::range_by_key(&self.entries, |(k, _)| k, range);
+        &self.entries[idx]
+    }
+
+    /// The number of entries with a key less than `key`.
+    pub fn rank(&self, key: &K) -> usize {
+        bounds::partition_point(&self.entries, |(k, _)| k < key)
+    }
+
+    /// The entry at position `k` in key order, counting from 0.
+    pub fn select(&self, k: usize) -> Option<&(K, V)> {
+        self.entries.get(k)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::sorted_collections::Duplicates;
+    use super::SortedMap;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = SortedMap::new(Duplicates::Reject);
+        assert!(map.insert("oslo", 3));
+        assert!(map.insert("lima", 1));
+        assert!(map.insert("pune", 2));
+        assert!(!map.insert("lima", 9));
+        assert_eq!(map.get(&"lima"), Some(&1));
+        assert_eq!(map.get(&"rome"), None);
+        *map.get_mut(&"oslo").unwrap() += 10;
+        assert_eq!(map.get(&"oslo"), Some(&13));
+        assert_eq!(map.remove(&"pune"), Some(2));
+        assert!(!map.contains_key(&"pune"));
+        let keys: Vec<&str> = map.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec!["lima", "oslo"]);
+    }
+
+    #[test]
+    fn multimap() {
+        let entries = vec![(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (3, 'e')];
+        let mut map = SortedMap::from_unsorted(&entries, Duplicates::Allow);
+        assert_eq!(map.get_all(&3), &[(3, 'a'), (3, 'c'), (3, 'e')]);
+        assert!(map.insert(3, 'f'));
+        assert_eq!(map.get_all(&3).last(), Some(&(3, 'f')));
+        assert_eq!(map.get(&3), Some(&'a'));
+        let unique = SortedMap::from_unsorted(&entries, Duplicates::Reject);
+        assert_eq!(unique.iter().cloned().collect::<Vec<_>>(), vec![(1, 'b'), (2, 'd'), (3, 'a')]);
+    }
+
+    #[test]
+    fn range_rank_select() {
+        let entries: Vec<(i32, i32)> = (0..10).map(|i| (i * 10, i)).collect();
+        let map = SortedMap::from_unsorted(&entries, Duplicates::Reject);
+        assert_eq!(map.range(15..=40), &[(20, 2), (30, 3), (40, 4)]);
+        assert_eq!(map.range(85..), &[(90, 9)]);
+        assert_eq!(map.rank(&35), 4);
+        assert_eq!(map.select(4), Some(&(40, 4)));
+    }
+}
@@ -0,0 +1,158 @@
+//! A set (or multiset) of values, kept in a sorted `Vec`.
+
+use std::ops::RangeBounds;
+
+use crate::search::bounds;
+use crate::sorting::merge;
+use super::Duplicates;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortedVec<T> {
+    values: Vec<T>,
+    duplicates: Duplicates,
+}
+
+
+impl<T: Ord + Clone> SortedVec<T> {
+    pub fn new(duplicates: Duplicates) -> Self {
+        Self { values: vec![], duplicates }
+    }
+
+    /// Build from values in any order, in O(n log n), with the stable merge sort. When
+    /// duplicates are rejected, only the first of a run of equal values is kept.
+    pub fn from_unsorted(values: &[T], duplicates: Duplicates) -> Self {
+        let mut values = merge::second::sort_by(values, Ord::cmp);
+        if duplicates == Duplicates::Reject {
+            values.dedup();
+        }
+        Self { values, duplicates }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.values.iter()
+    }
+
+    /// Insert `x` after any values equal to it, and return whether it was inserted: it
+    /// isn't, if it's already present and duplicates are rejected.
+    pub fn insert(&mut self, x: T) -> bool {
+        let idx = bounds::upper_bound(&self.values, &x);
+        if self.duplicates == Duplicates::Reject && idx > 0 && self.values[idx-1] == x {
+            return false
+        }
+        self.values.insert(idx, x);
+        true
+    }
+
+    /// Remove one value equal to `x`, returning it if there was one.
+    pub fn remove(&mut self, x: &T) -> Option<T> {
+        let idx = bounds::first_occurrence(&self.values, x)?;
+        Some(self.values.remove(idx))
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        bounds::first_occurrence(&self.values, x).is_some()
+    }
+
+    /// The number of values equal to `x`.
+    pub fn count(&self, x: &T) -> usize {
+        bounds::equal_range(&self.values, x).len()
+    }
+
+    /// All values within `range`, in order.
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> &[T] {
+        let idx = bounds::range_by_key(&self.values, |v| v, range);
+        &self.values[idx]
+    }
+
+    /// The number of values less than `x`.
+    pub fn rank(&self, x: &T) -> usize {
+        bounds::lower_bound(&self.values, x)
+    }
+
+    /// The `k`th smallest value, counting from 0.
+    pub fn select(&self, k: usize) -> Option<&T> {
+        self.values.get(k)
+    }
+}
+
+
+impl<T: Ord + Clone> Extend<T> for SortedVec<T> {
+    /// Append everything, then sort once, rather than inserting one value at a time.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.values.extend(iter);
+        *self = Self::from_unsorted(&self.values, self.duplicates);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::sorted_collections::Duplicates;
+    use super::SortedVec;
+
+    #[test]
+    fn insert_remove_contains() {
+        let mut set = SortedVec::new(Duplicates::Reject);
+        for x in [5, 1, 4, 1, 3, 5] {
+            set.insert(x);
+        }
+        assert_eq!(set.as_slice(), &[1, 3, 4, 5]);
+        assert!(!set.insert(4));
+        assert!(set.contains(&3));
+        assert!(!set.contains(&2));
+        assert_eq!(set.remove(&3), Some(3));
+        assert_eq!(set.remove(&3), None);
+        assert_eq!(set.as_slice(), &[1, 4, 5]);
+
+        let mut multiset = SortedVec::new(Duplicates::Allow);
+        for x in [5, 1, 4, 1, 3, 5] {
+            assert!(multiset.insert(x));
+        }
+        assert_eq!(multiset.as_slice(), &[1, 1, 3, 4, 5, 5]);
+        assert_eq!(multiset.count(&5), 2);
+        multiset.remove(&5);
+        assert_eq!(multiset.count(&5), 1);
+    }
+
+    #[test]
+    fn from_unsorted() {
+        let values = vec![0, 2, 1, 4, 7, 3, 2, 0];
+        let set = SortedVec::from_unsorted(&values, Duplicates::Reject);
+        assert_eq!(set.as_slice(), &[0, 1, 2, 3, 4, 7]);
+        let mut multiset = SortedVec::from_unsorted(&values, Duplicates::Allow);
+        assert_eq!(multiset.as_slice(), &[0, 0, 1, 2, 2, 3, 4, 7]);
+        multiset.extend([9, -1, 3]);
+        assert_eq!(multiset.as_slice(), &[-1, 0, 0, 1, 2, 2, 3, 3, 4, 7, 9]);
+    }
+
+    #[test]
+    fn range_rank_select() {
+        let set = SortedVec::from_unsorted(&[10, 40, 20, 50, 30], Duplicates::Reject);
+        assert_eq!(set.range(20..40), &[20, 30]);
+        assert_eq!(set.range(20..=40), &[20, 30, 40]);
+        assert_eq!(set.range(..25), &[10, 20]);
+        assert_eq!(set.range(45..), &[50]);
+        assert_eq!(set.range(41..45), &[] as &[i32]);
+        assert_eq!(set.rank(&30), 2);
+        assert_eq!(set.rank(&35), 3);
+        assert_eq!(set.rank(&0), 0);
+        assert_eq!(set.select(2), Some(&30));
+        assert_eq!(set.select(5), None);
+        for k in 0..set.len() {
+            assert_eq!(set.rank(set.select(k).unwrap()), k);
+        }
+    }
+}
@@ -24,14 +24,18 @@ fn swap_elements<T>(values: &mut [T], i: usize, j: usize) {
 }
 
 
+/// Swap two elements through a pointer to the start of the slice.
+/// NOTE: Both pointers are derived from the same `as_mut_ptr()`, so neither one
+/// invalidates the other, and `ptr::swap` allows them to be equal. The comparison that
+/// decides on a swap happens before any write, so a panicking `PartialOrd` can only ever
+/// interrupt the sort between complete swaps: no element is duplicated or lost.
 fn swap_elements_offset<T>(values: &mut [T], i: usize, j: usize) {
-    if i >= values.len() || j >= values.len() {
-        panic!("index out of bounds");
-    }
+    assert!(i < values.len() && j < values.len(), "index out of bounds");
     let ptr = values.as_mut_ptr();
+    // SAFETY: both indices were checked to be in bounds of the slice
     unsafe {
-        let pa = ptr.offset(i as isize);
-        let pb = ptr.offset(j as isize);
+        let pa = ptr.add(i);
+        let pb = ptr.add(j);
         std::ptr::swap(pa, pb);
     }
 }
@@ -53,6 +57,8 @@ fn sort_ptr<T: PartialOrd>(values: &mut [T]) {
 
 #[cfg(test)]
 mod tests {
+    use crate::sorting::panic_safety;
+
     #[test]
     fn sort() {
         let mut cases: Vec<Vec<i32>> = vec![
@@ -88,4 +94,9 @@ mod tests {
             assert_eq!(case, expect);
         }
     }
+
+    #[test]
+    fn sort_ptr_panic_safe() {
+        panic_safety::assert_panic_safe(super::sort_ptr);
+    }
 }
@@ -0,0 +1,148 @@
+//! Sorting a `LinkedList`. Merge sort splits the list by relinking nodes with
+//! `split_off()`, and merges the halves with `merge::second::merge_iter()`, straight from
+//! the lists' iterators, so that it shares its ordering, stability and handling of values
+//! that can't be compared with the other merge sorts. Insertion sort only relinks nodes,
+//! so no element is ever copied.
+//! NOTE: `split_off()` walks to the split position from the nearer end of the list, so
+//! each split is linear in the distance walked, just like the merge itself.
+
+use std::collections::LinkedList;
+
+use crate::sorting::merge;
+
+
+/// Merge two sorted lists. If one list is entirely before the other, as in
+/// `merge::second::merge()`, they are just relinked.
+fn merge<T>(mut left: LinkedList<T>, mut right: LinkedList<T>) -> LinkedList<T>
+where
+    T: Clone + PartialOrd
+{
+    // early return
+    if left.back() <= right.front() {
+        left.append(&mut right);
+        return left
+    } else if right.back() < left.front() {
+        right.append(&mut left);
+        return right
+    }
+    let mut out = Vec::with_capacity(left.len() + right.len());
+    merge::second::merge_iter(left.iter(), right.iter(), &mut out);
+    out.into_iter().collect()
+}
+
+
+pub fn merge_sort<T: Clone + PartialOrd>(list: &mut LinkedList<T>) {
+    if list.len() <= 1 {
+        return
+    }
+    let mut right = list.split_off(list.len() / 2);
+    merge_sort(list);
+    merge_sort(&mut right);
+    let left = std::mem::take(list);
+    *list = merge(left, right);
+}
+
+
+/// Unlink the front node one at a time and link it back into the sorted list. The
+/// insertion point is found by scanning from the back, so that nearly sorted lists only
+/// cost O(n), the same as insertion sort on a slice.
+pub fn insertion_sort<T: PartialOrd>(list: &mut LinkedList<T>) {
+    let mut sorted = LinkedList::new();
+    while !list.is_empty() {
+        let rest = list.split_off(1);
+        let mut node = std::mem::replace(list, rest);
+        let x = node.front().expect("a single node was just split off");
+        // insert after any equal values, to keep the sort stable
+        let n_greater = sorted.iter().rev().take_while(|v| *v > x).count();
+        let mut tail = sorted.split_off(sorted.len() - n_greater);
+        sorted.append(&mut node);
+        sorted.append(&mut tail);
+    }
+    *list = sorted;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::LinkedList;
+
+    #[test]
+    fn merge_sort() {
+        let mut cases: Vec<LinkedList<i32>> = vec![
+            LinkedList::from([0, 2, 1, 4, 7, 3]),
+            LinkedList::from([2, -1, 5, 2, 9]),
+            LinkedList::from([0, 1, 2, 4, 3, 0]),
+            LinkedList::new(),
+        ];
+        let expected: Vec<LinkedList<i32>> = vec![
+            LinkedList::from([0, 1, 2, 3, 4, 7]),
+            LinkedList::from([-1, 2, 2, 5, 9]),
+            LinkedList::from([0, 0, 1, 2, 3, 4]),
+            LinkedList::new(),
+        ];
+        for (case, expect) in cases.iter_mut().zip(&expected) {
+            super::merge_sort(case);
+            assert_eq!(case, expect);
+        }
+    }
+
+    #[test]
+    fn insertion_sort() {
+        let mut cases: Vec<LinkedList<i32>> = vec![
+            LinkedList::from([0, 2, 1, 4, 7, 3]),
+            LinkedList::from([2, -1, 5, 2, 9]),
+            LinkedList::from([0, 1, 2, 4, 3, 0]),
+            LinkedList::new(),
+        ];
+        let expected: Vec<LinkedList<i32>> = vec![
+            LinkedList::from([0, 1, 2, 3, 4, 7]),
+            LinkedList::from([-1, 2, 2, 5, 9]),
+            LinkedList::from([0, 0, 1, 2, 3, 4]),
+            LinkedList::new(),
+        ];
+        for (case, expect) in cases.iter_mut().zip(&expected) {
+            super::insertion_sort(case);
+            assert_eq!(case, expect);
+        }
+    }
+
+    #[test]
+    fn stable() {
+        // sort by the key only, and check the tags of equal keys keep their order
+        #[derive(Debug, Clone, PartialEq)]
+        struct Item(i32, char);
+        impl PartialOrd for Item {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+        let items = [(2, 'a'), (1, 'b'), (2, 'c'), (0, 'd'), (1, 'e'), (2, 'f')];
+        let expected: Vec<(i32, char)> = vec![
+            (0, 'd'), (1, 'b'), (1, 'e'), (2, 'a'), (2, 'c'), (2, 'f'),
+        ];
+        let sorters: [fn(&mut LinkedList<Item>); 2] = [super::merge_sort, super::insertion_sort];
+        for sort in sorters {
+            let mut list: LinkedList<Item> = items.iter().map(|&(k, t)| Item(k, t)).collect();
+            sort(&mut list);
+            let result: Vec<(i32, char)> = list.iter().map(|i| (i.0, i.1)).collect();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn incomparable() {
+        // `NaN`s can't be ordered, but sorting has to terminate and keep every value
+        let sorters: [fn(&mut LinkedList<f64>); 2] = [super::merge_sort, super::insertion_sort];
+        for sort in sorters {
+            for values in [vec![1.0, f64::NAN, 0.5], vec![f64::NAN, 3.0, f64::NAN, 2.0, 1.0]] {
+                let mut list: LinkedList<f64> = values.iter().copied().collect();
+                sort(&mut list);
+                let mut result: Vec<f64> = list.into_iter().collect();
+                let mut expected = values.clone();
+                result.sort_by(f64::total_cmp);
+                expected.sort_by(f64::total_cmp);
+                assert_eq!(format!("{result:?}"), format!("{expected:?}"));
+            }
+        }
+    }
+}
@@ -0,0 +1,5 @@
+//! Sorting `std::collections` types directly, rather than first copying them into a slice
+//! (or, for `VecDeque`, making them contiguous).
+
+pub mod linked_list;
+pub mod vec_deque;
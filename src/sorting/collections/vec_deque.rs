@@ -0,0 +1,115 @@
+//! Sorting a `VecDeque` in place, through its indices, without making it contiguous.
+
+use std::collections::VecDeque;
+
+use crate::sorting::merge;
+
+
+/// Bottom-up merge sort: merge neighbouring runs of `width` elements, doubling `width`
+/// each pass. Each merge reads both runs straight out of the deque with `range()`, goes
+/// through `merge::second::merge_iter()`, and is written back over the same range.
+pub fn merge_sort<T: Clone + PartialOrd>(values: &mut VecDeque<T>) {
+    let n = values.len();
+    let mut buffer = Vec::with_capacity(n);
+    let mut width = 1;
+    while width < n {
+        for lo in (0..n).step_by(2 * width) {
+            let mid = (lo + width).min(n);
+            let hi = (lo + 2 * width).min(n);
+            // early return, as in `merge::second::merge()`: the runs are already in order
+            if mid == hi || values[mid-1] <= values[mid] {
+                continue
+            }
+            buffer.clear();
+            merge::second::merge_iter(values.range(lo..mid), values.range(mid..hi), &mut buffer);
+            for (slot, x) in values.range_mut(lo..hi).zip(buffer.drain(..)) {
+                *slot = x;
+            }
+        }
+        width *= 2;
+    }
+}
+
+
+pub fn insertion_sort<T: PartialOrd>(values: &mut VecDeque<T>) {
+    for i in 1..values.len() {
+        let mut k = i;
+        while k > 0 && values[k] < values[k-1] {
+            values.swap(k, k-1);
+            k -= 1;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    /// Build a deque whose contents wrap around the end of its buffer, so that sorting it
+    /// is only correct if the sort never assumes it is contiguous.
+    fn wrapped(values: &[i32]) -> VecDeque<i32> {
+        let mut deque = VecDeque::with_capacity(values.len());
+        let half = values.len() / 2;
+        for x in values[..half].iter().rev() {
+            deque.push_front(*x);
+        }
+        for x in &values[half..] {
+            deque.push_back(*x);
+        }
+        deque
+    }
+
+    #[test]
+    fn merge_sort() {
+        let cases: Vec<Vec<i32>> = vec![
+            vec![0, 2, 1, 4, 7, 3],
+            vec![2, -1, 5, 2, 9],
+            vec![0, 1, 2, 4, 3, 0],
+            vec![],
+        ];
+        let expected: Vec<Vec<i32>> = vec![
+            vec![0, 1, 2, 3, 4, 7],
+            vec![-1, 2, 2, 5, 9],
+            vec![0, 0, 1, 2, 3, 4],
+            vec![],
+        ];
+        for (case, expect) in cases.iter().zip(&expected) {
+            let mut deque = wrapped(case);
+            super::merge_sort(&mut deque);
+            assert_eq!(deque, *expect);
+        }
+    }
+
+    #[test]
+    fn insertion_sort() {
+        let cases: Vec<Vec<i32>> = vec![
+            vec![0, 2, 1, 4, 7, 3],
+            vec![2, -1, 5, 2, 9],
+            vec![0, 1, 2, 4, 3, 0],
+            vec![],
+        ];
+        let expected: Vec<Vec<i32>> = vec![
+            vec![0, 1, 2, 3, 4, 7],
+            vec![-1, 2, 2, 5, 9],
+            vec![0, 0, 1, 2, 3, 4],
+            vec![],
+        ];
+        for (case, expect) in cases.iter().zip(&expected) {
+            let mut deque = wrapped(case);
+            super::insertion_sort(&mut deque);
+            assert_eq!(deque, *expect);
+        }
+    }
+
+    #[test]
+    fn merge_sort_large() {
+        let values: Vec<i32> = (0..1000).map(|i| (i * 7919) % 1000 - 500).collect();
+        let mut expected = values.clone();
+        expected.sort();
+        let mut deque = wrapped(&values);
+        assert!(!deque.as_slices().1.is_empty());
+        super::merge_sort(&mut deque);
+        assert_eq!(deque, expected);
+    }
+}
@@ -0,0 +1,80 @@
+//! Cycle sort: every element is written directly into its final position, so the number
+//! of writes is the minimum possible: one per element that is out of place. The price
+//! is O(n^2) comparisons, since each position is found by counting smaller elements.
+
+
+/// The position where `item` belongs, given that everything before `start` is already
+/// in place: `start` plus the number of smaller elements after `start`.
+fn find_position<T: PartialOrd>(values: &[T], item: &T, start: usize) -> usize {
+    start + values[start+1..].iter().filter(|v| *v < item).count()
+}
+
+
+/// Sort the values in place and return the number of writes made to `values`.
+/// For each starting position, the element there is carried around its cycle of the
+/// permutation: it is swapped into its final position, and the element that was there is
+/// carried on in turn, until the cycle closes back at the start.
+pub fn sort<T: PartialOrd + Clone>(values: &mut [T]) -> usize {
+    let mut writes = 0;
+    for start in 0..values.len().saturating_sub(1) {
+        let mut item = values[start].clone();
+        let mut pos = find_position(values, &item, start);
+        if pos == start {
+            continue
+        }
+        // equal elements go after any that were already placed
+        while item == values[pos] {
+            pos += 1;
+        }
+        std::mem::swap(&mut item, &mut values[pos]);
+        writes += 1;
+        while pos != start {
+            pos = find_position(values, &item, start);
+            while item == values[pos] {
+                pos += 1;
+            }
+            std::mem::swap(&mut item, &mut values[pos]);
+            writes += 1;
+        }
+    }
+    writes
+}
+
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn sort() {
+        let mut cases: Vec<Vec<i32>> = vec![
+            vec![0, 2, 1, 4, 7, 3],
+            vec![2, -1, 5, 2, 9],
+            vec![0, 1, 2, 4, 3, 0],
+            vec![1, 1, 0],
+            vec![],
+        ];
+        let expected: Vec<(Vec<i32>, usize)> = vec![
+            (vec![0, 1, 2, 3, 4, 7], 5),
+            (vec![-1, 2, 2, 5, 9], 4),
+            (vec![0, 0, 1, 2, 3, 4], 4),
+            (vec![0, 1, 1], 2),
+            (vec![], 0),
+        ];
+        for (case, expect) in cases.iter_mut().zip(&expected) {
+            let writes = super::sort(case);
+            assert_eq!(case, &expect.0);
+            assert_eq!(writes, expect.1);
+        }
+    }
+
+    #[test]
+    fn sort_minimal_writes() {
+        // the number of writes is exactly the number of elements that are out of place
+        let mut values: Vec<i32> = (0..50).map(|i| (i * 37) % 50 / 3).collect();
+        let mut sorted = values.clone();
+        sorted.sort();
+        let misplaced = values.iter().zip(&sorted).filter(|(a, b)| a != b).count();
+        let writes = super::sort(&mut values);
+        assert_eq!(values, sorted);
+        assert_eq!(writes, misplaced);
+    }
+}
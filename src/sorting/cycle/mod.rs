@@ -0,0 +1,6 @@
+//! Sorting with as few writes as possible, for when writing to the underlying storage is
+//! far more expensive than reading from it (e.g. flash memory with limited write cycles).
+//! Both implementations return the number of writes that were made to the slice.
+
+pub mod classic;
+pub mod permutation;
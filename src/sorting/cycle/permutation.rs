@@ -0,0 +1,97 @@
+//! Sorting by first computing the permutation that sorts the values, without touching
+//! them, and then applying it. Computing the permutation uses O(n log n) comparisons,
+//! as opposed to the O(n^2) of `classic::sort()`, at the cost of O(n) extra memory.
+//! Every position is written at most once.
+//! NOTE: With duplicates this can write more than `classic::sort()`, because the stable
+//! permutation may move an element into a position that already holds an equal value.
+
+use std::fmt::Debug;
+
+use crate::sorting::merge;
+
+
+/// Stable argsort: `order[j]` is the index of the element that belongs at `j`.
+pub fn argsort<T: PartialOrd + Debug>(values: &[T]) -> Vec<usize> {
+    // sort references to avoid cloning the values themselves; the index breaks ties,
+    // which keeps equal values in their original order
+    let indexed: Vec<(&T, usize)> = values.iter().zip(0..).collect();
+    merge::second::sort(&indexed)
+        .into_iter()
+        .map(|(_, idx)| idx)
+        .collect()
+}
+
+
+/// Rearrange `values` so that `values[j]` becomes the old `values[order[j]]`, and return
+/// the number of writes. Each cycle of the permutation is followed from its start: the
+/// value at the start is held aside, every position in the cycle is filled from the next
+/// one, and the held value fills the last one. Positions already in place are not
+/// written at all.
+pub fn apply<T: Clone>(values: &mut [T], order: &[usize]) -> usize {
+    assert_eq!(values.len(), order.len(), "permutation length must match");
+    let mut writes = 0;
+    let mut placed = vec![false; values.len()];
+    for start in 0..values.len() {
+        if placed[start] || order[start] == start {
+            continue
+        }
+        let held = values[start].clone();
+        let mut j = start;
+        loop {
+            placed[j] = true;
+            let src = order[j];
+            writes += 1;
+            if src == start {
+                values[j] = held;
+                break
+            }
+            values[j] = values[src].clone();
+            j = src;
+        }
+    }
+    writes
+}
+
+
+/// Sort the values in place and return the number of writes made to `values`.
+pub fn sort<T: PartialOrd + Clone + Debug>(values: &mut [T]) -> usize {
+    let order = argsort(values);
+    apply(values, &order)
+}
+
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn sort() {
+        let mut cases: Vec<Vec<i32>> = vec![
+            vec![0, 2, 1, 4, 7, 3],
+            vec![2, -1, 5, 2, 9],
+            vec![0, 1, 2, 4, 3, 0],
+            vec![1, 1, 0],
+            vec![],
+        ];
+        let expected: Vec<(Vec<i32>, usize)> = vec![
+            (vec![0, 1, 2, 3, 4, 7], 5),
+            (vec![-1, 2, 2, 5, 9], 4),
+            (vec![0, 0, 1, 2, 3, 4], 4),
+            (vec![0, 1, 1], 3),
+            (vec![], 0),
+        ];
+        for (case, expect) in cases.iter_mut().zip(&expected) {
+            let writes = super::sort(case);
+            assert_eq!(case, &expect.0);
+            assert_eq!(writes, expect.1);
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let mut values = vec!['a', 'b', 'c', 'd', 'e'];
+        let order = [3, 0, 2, 4, 1];
+        let writes = super::apply(&mut values, &order);
+        assert_eq!(values, vec!['d', 'a', 'c', 'e', 'b']);
+        // 'c' is already in place
+        assert_eq!(writes, 4);
+    }
+}
@@ -39,23 +39,57 @@ pub fn sort_ptrs<T>(values: &mut [T])
 }
 
 
-/// Instead of using the swap method, directly and completely implement the swap.
-/// NOTE: this goes beyond the macro used in the `sort_prts` implementation.
+/// Fills the hole left in the slice by the element being inserted. Doing this on drop,
+/// rather than at the end of the insertion loop, means the element is written back even
+/// if a comparison panics, so the slice never ends up with a duplicated or lost element.
+struct InsertionHole<T> {
+    src: *const T,
+    dest: *mut T,
+}
+
+
+impl<T> Drop for InsertionHole<T> {
+    fn drop(&mut self) {
+        // SAFETY: `src` points to the element that was moved out, and `dest` is the
+        // hole in the slice, so they can never overlap.
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.src, self.dest, 1);
+        }
+    }
+}
+
+
+/// Instead of using the swap method, directly and completely implement the move.
+/// NOTE: this goes beyond the macro used in the `sort_prts` implementation: rather than
+/// swapping neighbours, the element is read out of the slice once, larger elements are
+/// shifted up by one, and the element is written into the hole that is left over.
+/// All pointers are derived from a single `as_mut_ptr()`.
 pub fn sort_ptrs_manual<T>(values: &mut [T])
     where
         T: PartialEq + PartialOrd
 {
     let n = values.len();
+    let ptr = values.as_mut_ptr();
     for i in 1..n {
-        let mut k = i;
-        while k > 0 && values[k] < values[k-1] {
-            // get two raw pointers: one to each value in memory
-            unsafe {
-                let pa = values.as_ptr().offset(k as isize) as *mut T;
-                let pb = values.as_ptr().offset((k-1) as isize) as *mut T;
-                std::ptr::swap(pa, pb);
+        // SAFETY: every offset is below `i < n`, and the hole guard puts `tmp` back
+        // into the slice exactly once, whether or not a comparison panics.
+        unsafe {
+            let out_of_place = *ptr.add(i) < *ptr.add(i-1);
+            if !out_of_place {
+                continue
             }
-            k -= 1;
+            let tmp = std::mem::ManuallyDrop::new(std::ptr::read(ptr.add(i)));
+            let mut hole = InsertionHole { src: &*tmp, dest: ptr.add(i-1) };
+            std::ptr::copy_nonoverlapping(ptr.add(i-1), ptr.add(i), 1);
+            for k in (0..i-1).rev() {
+                let shift = *tmp < *ptr.add(k);
+                if !shift {
+                    break
+                }
+                std::ptr::copy_nonoverlapping(ptr.add(k), ptr.add(k+1), 1);
+                hole.dest = ptr.add(k);
+            }
+            // `hole` is dropped here, moving `tmp` into its final position
         }
     }
 }
@@ -63,6 +97,8 @@ pub fn sort_ptrs_manual<T>(values: &mut [T])
 
 #[cfg(test)]
 mod tests {
+    use crate::sorting::panic_safety;
+
     #[test]
     fn sort() {
         let mut cases: Vec<Vec<i32>> = vec![
@@ -116,4 +152,9 @@ mod tests {
             assert_eq!(case, expect);
         }
     }
+
+    #[test]
+    fn sort_ptrs_manual_panic_safe() {
+        panic_safety::assert_panic_safe(super::sort_ptrs_manual);
+    }
 }
@@ -1,2 +1,2 @@
-mod first;
+pub mod first;
 mod second;
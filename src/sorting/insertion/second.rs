@@ -46,19 +46,23 @@ fn swap_elements<T: PartialOrd>(values: &mut [T], i: usize, j: usize) {
 
 
 /// Manual implementation of swapping slice elements using slice pointer with offset.
-/// NOTE: Uses unsafe code; similar to `swap_elements()`.
+/// NOTE: Uses unsafe code; similar to `swap_elements()`. The pointer has to come from
+/// `as_mut_ptr()`: writing through one cast from `as_ptr()` is undefined behaviour, since
+/// it was derived from a shared borrow.
 fn swap_elements_offset<T: PartialOrd>(values: &mut [T], i: usize, j: usize) {
-    if i >= values.len() || j >= values.len() {
-        panic!("index out of bounds");
-    }
+    assert!(i < values.len() && j < values.len(), "index out of bounds");
+    let ptr = values.as_mut_ptr();
+    // SAFETY: both indices were checked to be in bounds of the slice
     unsafe {
-        let pa = values.as_ptr().offset(i as isize) as *mut T;
-        let pb = values.as_ptr().offset(j as isize) as *mut T;
+        let pa = ptr.add(i);
+        let pb = ptr.add(j);
         std::ptr::swap(pa, pb);
     }
 }
 
 
+/// Each comparison happens before the swap it decides on, so a panicking `PartialOrd`
+/// leaves the slice as a permutation of its original elements.
 fn sort_inplace_ptrs<T: PartialOrd>(values: &mut [T]) {
     for i in 1..values.len() {
         for j in (1..=i).rev() {
@@ -70,6 +74,7 @@ fn sort_inplace_ptrs<T: PartialOrd>(values: &mut [T]) {
 
 #[cfg(test)]
 mod tests {
+    use crate::sorting::panic_safety;
 
     #[test]
     fn sort_inplace() {
@@ -125,4 +130,9 @@ mod tests {
         }
     }
 
+    #[test]
+    fn sort_inplace_ptrs_panic_safe() {
+        panic_safety::assert_panic_safe(super::sort_inplace_ptrs);
+    }
+
 }
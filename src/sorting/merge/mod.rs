@@ -1,2 +1,2 @@
-mod first;
-mod second;
+pub mod first;
+pub mod second;
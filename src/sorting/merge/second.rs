@@ -1,9 +1,9 @@
 //! Second implementation of mergesort.
 
-use std::fmt::Debug;
+use std::{cmp::Ordering, fmt::Debug};
 
 
-fn merge<T: Debug + Clone + PartialOrd>(left: &[T], right: &[T]) -> Vec<T> {
+pub fn merge<T: Debug + Clone + PartialOrd>(left: &[T], right: &[T]) -> Vec<T> {
     let mut out = Vec::with_capacity(left.len() + right.len());
     // early return
     if left.last()  < right.first() {
@@ -15,24 +15,82 @@ fn merge<T: Debug + Clone + PartialOrd>(left: &[T], right: &[T]) -> Vec<T> {
         out.extend_from_slice(left);
         return out
     }
-    let mut xi: usize = 0;
-    let mut yi: usize = 0;
-    while xi < left.len() && yi < right.len() {
-        if left[xi] > right[yi] {
-            out.push(right[yi].clone());
-            yi += 1;
+    merge_iter(left.iter(), right.iter(), &mut out);
+    out
+}
+
+
+/// The merge step of `merge()`, over any two sorted sequences of references, which lets
+/// sequences that aren't slices (e.g. ranges of a `VecDeque`) be merged as well.
+/// Ties are taken from `left`, which keeps the merge stable.
+pub fn merge_iter<'a, T, L, R>(left: L, right: R, out: &mut Vec<T>)
+where
+    T: Clone + PartialOrd + 'a,
+    L: ExactSizeIterator<Item = &'a T>,
+    R: Iterator<Item = &'a T>,
+{
+    merge_into(left, right, out, &mut partial_compare);
+}
+
+
+/// `PartialOrd` as an ordering, where values that can't be compared are equal, so they
+/// are taken from `left` first.
+fn partial_compare<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+
+/// The merge that all the others are built on: merge `left` and `right` by `compare`
+/// into `out`, taking ties from `left`, and return the number of inversions between
+/// them. Each time a value is taken from `right`, it jumps over every value that remains
+/// in `left`.
+fn merge_into<'a, T, L, R, F>(left: L, right: R, out: &mut Vec<T>, compare: &mut F) -> usize
+where
+    T: Clone + 'a,
+    L: ExactSizeIterator<Item = &'a T>,
+    R: Iterator<Item = &'a T>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut inversions: usize = 0;
+    let mut left = left.peekable();
+    let mut right = right.peekable();
+    while let (Some(x), Some(y)) = (left.peek(), right.peek()) {
+        if compare(x, y) == Ordering::Greater {
+            out.push((*y).clone());
+            inversions += left.len();
+            right.next();
         } else {
-            out.push(left[xi].clone());
-            xi += 1;
+            out.push((*x).clone());
+            left.next();
         }
     }
-    out.extend_from_slice(&left[xi..]);
-    out.extend_from_slice(&right[yi..]);
-    out
+    out.extend(left.cloned());
+    out.extend(right.cloned());
+    inversions
 }
 
 
-fn sort<T: Debug + Clone + PartialOrd>(values: &[T]) -> Vec<T> {
+/// Same as `merge()`, but also counts the number of inversions between the two halves,
+/// i.e. the number of pairs `(x, y)`, with `x` from `left` and `y` from `right`, where
+/// `x > y`.
+pub fn merge_count<T: Debug + Clone + PartialOrd>(left: &[T], right: &[T]) -> (Vec<T>, usize) {
+    let mut out = Vec::with_capacity(left.len() + right.len());
+    // early return
+    if left.last() <= right.first() {
+        out.extend_from_slice(left);
+        out.extend_from_slice(right);
+        return (out, 0)
+    } else if right.last() < left.first() {
+        out.extend_from_slice(right);
+        out.extend_from_slice(left);
+        return (out, left.len() * right.len())
+    }
+    let inversions = merge_into(left.iter(), right.iter(), &mut out, &mut partial_compare);
+    (out, inversions)
+}
+
+
+pub fn sort<T: Debug + Clone + PartialOrd>(values: &[T]) -> Vec<T> {
      let n_values = values.len();
      if n_values <= 1 {
          return values.to_vec()
@@ -45,6 +103,44 @@ fn sort<T: Debug + Clone + PartialOrd>(values: &[T]) -> Vec<T> {
  }
 
 
+/// Like `merge()`, but ordered by `compare` rather than by `PartialOrd`.
+/// Ties are taken from `left`, which keeps the merge stable.
+pub fn merge_by<T, F>(left: &[T], right: &[T], compare: &mut F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut out = Vec::with_capacity(left.len() + right.len());
+    merge_into(left.iter(), right.iter(), &mut out, compare);
+    out
+}
+
+
+/// Stable sort by a comparison function.
+pub fn sort_by<T, F>(values: &[T], mut compare: F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort_by_recurse(values, &mut compare)
+}
+
+
+fn sort_by_recurse<T, F>(values: &[T], compare: &mut F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if values.len() <= 1 {
+        return values.to_vec()
+    }
+    let mid = values.len() / 2;
+    let left = sort_by_recurse(&values[..mid], compare);
+    let right = sort_by_recurse(&values[mid..], compare);
+    merge_by(&left, &right, compare)
+}
+
+
 #[cfg(test)]
 mod tests {
 
@@ -66,4 +162,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn sort_by() {
+        let mut cases: Vec<Vec<i32>> = vec![
+            vec![0, 2, 1, 4, 7, 3],
+            vec![2, -1, 5, 2, 9],
+            vec![0, 1, 2, 4, 3, 0],
+        ];
+        let expected: Vec<Vec<i32>> = vec![
+            vec![7, 4, 3, 2, 1, 0],
+            vec![9, 5, 2, 2, -1],
+            vec![4, 3, 2, 1, 0, 0],
+        ];
+        for (case, expect) in cases.iter_mut().zip(&expected) {
+            let result = super::sort_by(case, |a, b| b.cmp(a));
+            assert_eq!(&result, expect);
+        }
+    }
+
+    #[test]
+    fn merge_count() {
+        let cases: Vec<(Vec<i32>, Vec<i32>)> = vec![
+            (vec![1, 2, 3], vec![4, 5]),
+            (vec![4, 5], vec![1, 2, 3]),
+            (vec![1, 4, 6], vec![2, 3, 5]),
+            (vec![2, 2], vec![2, 2]),
+            (vec![], vec![1]),
+        ];
+        let expected: Vec<(Vec<i32>, usize)> = vec![
+            (vec![1, 2, 3, 4, 5], 0),
+            (vec![1, 2, 3, 4, 5], 6),
+            (vec![1, 2, 3, 4, 5, 6], 5),
+            (vec![2, 2, 2, 2], 0),
+            (vec![1], 0),
+        ];
+        for ((left, right), expect) in cases.iter().zip(&expected) {
+            let result = super::merge_count(left, right);
+            assert_eq!(&result, expect);
+        }
+    }
 }
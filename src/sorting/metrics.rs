@@ -0,0 +1,211 @@
+//! Measures of presortedness: how far a sequence is from being sorted.
+//! These are used to pick a sorting algorithm that suits the data, rather than always
+//! paying for the worst case:
+//! - inversions: number of pairs `(i, j)` where `i < j` but `values[i] > values[j]`.
+//!   Insertion sort runs in O(n + inversions).
+//! - runs: number of maximal non-decreasing runs. A sorted sequence has one run.
+//! - max displacement: the furthest any element is from its (stable) sorted position.
+//! - LIS: length of the longest strictly increasing subsequence. `n - lis` is the
+//!   minimum number of elements that have to be moved to sort the sequence.
+
+use std::fmt::Debug;
+
+use super::{insertion, merge, quick};
+
+
+/// Sequences at most this long are always sorted with insertion sort.
+const SMALL: usize = 16;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metrics {
+    pub len: usize,
+    pub inversions: usize,
+    pub runs: usize,
+    pub max_displacement: usize,
+    pub lis: usize,
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Insertion,
+    Merge,
+    Quick,
+}
+
+
+impl Metrics {
+    pub fn new<T: Debug + Clone + PartialOrd>(values: &[T]) -> Self {
+        Self {
+            len: values.len(),
+            inversions: inversions(values),
+            runs: runs(values),
+            max_displacement: max_displacement(values),
+            lis: longest_increasing_subsequence(values),
+        }
+    }
+
+    /// Pick the sorting algorithm that is expected to do the least work:
+    /// 1. insertion sort for short sequences, or when there are no more inversions than
+    ///    elements, as its cost is linear in the number of inversions.
+    /// 2. merge sort when there are only a few long runs, because merging already
+    ///    ordered halves returns early.
+    /// 3. quicksort for everything else.
+    pub fn strategy(&self) -> Strategy {
+        if self.len <= SMALL || self.inversions <= self.len {
+            return Strategy::Insertion
+        }
+        // the number of merge levels needed if every run were merged pairwise
+        let levels = usize::BITS - self.len.leading_zeros();
+        if self.runs <= levels as usize {
+            return Strategy::Merge
+        }
+        Strategy::Quick
+    }
+}
+
+
+/// Count inversions in O(n log n) with a merge sort that counts while it merges.
+pub fn inversions<T: Debug + Clone + PartialOrd>(values: &[T]) -> usize {
+    count_recurse(values).1
+}
+
+
+fn count_recurse<T: Debug + Clone + PartialOrd>(values: &[T]) -> (Vec<T>, usize) {
+    if values.len() <= 1 {
+        return (values.to_vec(), 0)
+    }
+    let mid = values.len() / 2;
+    let (left, left_count) = count_recurse(&values[..mid]);
+    let (right, right_count) = count_recurse(&values[mid..]);
+    let (merged, count) = merge::second::merge_count(&left, &right);
+    (merged, left_count + right_count + count)
+}
+
+
+/// Number of maximal non-decreasing runs.
+pub fn runs<T: PartialOrd>(values: &[T]) -> usize {
+    if values.is_empty() {
+        return 0
+    }
+    1 + values.windows(2).filter(|w| w[1] < w[0]).count()
+}
+
+
+/// The largest distance between an element's index and its index after a stable sort.
+pub fn max_displacement<T: Debug + Clone + PartialOrd>(values: &[T]) -> usize {
+    // pairing each value with its index makes the order total and the sort stable
+    let indexed: Vec<(T, usize)> = values.iter().cloned().zip(0..).collect();
+    merge::second::sort(&indexed)
+        .iter()
+        .enumerate()
+        .map(|(sorted_idx, (_, idx))| sorted_idx.abs_diff(*idx))
+        .max()
+        .unwrap_or(0)
+}
+
+
+/// Length of the longest strictly increasing subsequence, using patience sorting:
+/// `tails[k]` holds the smallest value that ends an increasing subsequence of length
+/// `k + 1`, so `tails` stays sorted and can be binary searched.
+pub fn longest_increasing_subsequence<T: PartialOrd>(values: &[T]) -> usize {
+    let mut tails: Vec<&T> = vec![];
+    for x in values {
+        let pos = tails.partition_point(|t| *t < x);
+        if pos == tails.len() {
+            tails.push(x);
+        } else {
+            tails[pos] = x;
+        }
+    }
+    tails.len()
+}
+
+
+/// Sort `values` with the algorithm chosen by `Metrics::strategy()`, and return the
+/// strategy that was used.
+pub fn sort<T: Debug + Clone + PartialOrd>(values: &mut [T]) -> Strategy {
+    let strategy = Metrics::new(values).strategy();
+    match strategy {
+        Strategy::Insertion => insertion::first::sort(values),
+        Strategy::Merge => merge::first::sort_mutate(values),
+        Strategy::Quick => quick::second::sort_inplace(values),
+    }
+    strategy
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, SeedableRng, rngs::StdRng};
+
+    use super::Strategy;
+
+    #[test]
+    fn metrics() {
+        let cases: Vec<Vec<i32>> = vec![
+            vec![],
+            vec![0, 1, 2, 3, 4],
+            vec![4, 3, 2, 1, 0],
+            vec![0, 2, 1, 4, 7, 3],
+            vec![2, 2, 1, 1],
+        ];
+        // (inversions, runs, max displacement, lis)
+        let expected: Vec<(usize, usize, usize, usize)> = vec![
+            (0, 0, 0, 0),
+            (0, 1, 0, 5),
+            (10, 5, 4, 1),
+            (3, 3, 2, 4),
+            (4, 2, 2, 1),
+        ];
+        for (case, expect) in cases.iter().zip(&expected) {
+            let m = super::Metrics::new(case);
+            assert_eq!((m.inversions, m.runs, m.max_displacement, m.lis), *expect);
+        }
+    }
+
+    #[test]
+    fn inversions_brute_force() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut values: Vec<i32> = (0..200).map(|i| i % 37).collect();
+        values.shuffle(&mut rng);
+        let mut expected = 0;
+        for i in 0..values.len() {
+            for j in i+1..values.len() {
+                if values[i] > values[j] { expected += 1 }
+            }
+        }
+        assert_eq!(super::inversions(&values), expected);
+    }
+
+    #[test]
+    fn sort() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let n = 1000;
+        let sorted: Vec<i64> = (0..n).collect();
+        // nearly sorted: a handful of adjacent swaps
+        let mut nearly = sorted.clone();
+        for i in (0..n as usize - 1).step_by(100) {
+            nearly.swap(i, i + 1);
+        }
+        // two long runs
+        let mut two_runs: Vec<i64> = (0..n).step_by(2).collect();
+        two_runs.extend((1..n).step_by(2));
+        let mut shuffled = sorted.clone();
+        shuffled.shuffle(&mut rng);
+        let mut cases = [
+            (vec![3, 1, 2], Strategy::Insertion),
+            (nearly, Strategy::Insertion),
+            (two_runs, Strategy::Merge),
+            (shuffled, Strategy::Quick),
+        ];
+        for (case, expect) in cases.iter_mut() {
+            let mut expected_values = case.clone();
+            expected_values.sort();
+            let strategy = super::sort(case);
+            assert_eq!(strategy, *expect);
+            assert_eq!(case, &expected_values);
+        }
+    }
+}
@@ -1,4 +1,10 @@
 pub mod bubble;
+pub mod collections;
+pub mod cycle;
 pub mod insertion;
 pub mod merge;
+pub mod metrics;
+pub mod multi_key;
+#[cfg(test)]
+mod panic_safety;
 pub mod quick;
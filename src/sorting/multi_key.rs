@@ -0,0 +1,155 @@
+//! Sorting records (e.g. rows of a table) by several keys at once, each with its own
+//! direction and placement of missing values, like SQL's
+//! `ORDER BY a ASC NULLS LAST, b DESC NULLS FIRST`.
+//! The keys are compared lexicographically: the second key only breaks ties of the
+//! first, and so on. Rows that tie on every key keep their input order, since this is
+//! built on the stable `merge::second::sort_by()`.
+
+use std::cmp::Ordering;
+
+use crate::sorting::merge;
+
+type Compare<'a, R> = Box<dyn Fn(&R, &R) -> Ordering + 'a>;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Ascending,
+    Descending,
+}
+
+
+/// Where rows with a missing (`None`) key go. This does not depend on the direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nulls {
+    First,
+    Last,
+}
+
+
+/// A single sort key: a field extractor plus how to order by it.
+pub struct SortKey<'a, R> {
+    compare: Compare<'a, R>,
+}
+
+
+impl<'a, R> SortKey<'a, R> {
+    /// `extract` returns the key of a row, or `None` when it is missing. Keys that can't
+    /// be compared (e.g. `NaN`) are treated as equal.
+    pub fn new<K, F>(extract: F, direction: Direction, nulls: Nulls) -> Self
+    where
+        K: PartialOrd + ?Sized + 'a,
+        F: Fn(&R) -> Option<&K> + 'a,
+    {
+        let null_first = match nulls {
+            Nulls::First => Ordering::Less,
+            Nulls::Last => Ordering::Greater,
+        };
+        let compare = move |a: &R, b: &R| match (extract(a), extract(b)) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => null_first,
+            (Some(_), None) => null_first.reverse(),
+            (Some(x), Some(y)) => {
+                let ord = x.partial_cmp(y).unwrap_or(Ordering::Equal);
+                match direction {
+                    Direction::Ascending => ord,
+                    Direction::Descending => ord.reverse(),
+                }
+            },
+        };
+        Self { compare: Box::new(compare) }
+    }
+
+    pub fn compare(&self, a: &R, b: &R) -> Ordering {
+        (self.compare)(a, b)
+    }
+}
+
+
+/// Compare two rows key by key, returning the first ordering that isn't a tie.
+pub fn compare_by_keys<R>(keys: &[SortKey<R>], a: &R, b: &R) -> Ordering {
+    keys.iter()
+        .map(|key| key.compare(a, b))
+        .find(|ord| *ord != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+
+/// Stable sort of `rows` by `keys`, in order of priority.
+pub fn sort_by_keys<R: Clone>(rows: &[R], keys: &[SortKey<R>]) -> Vec<R> {
+    merge::second::sort_by(rows, |a, b| compare_by_keys(keys, a, b))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::sorting::merge;
+    use super::{Direction, Nulls, SortKey};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Row {
+        id: usize,
+        city: Option<String>,
+        age: Option<u32>,
+        score: f64,
+    }
+
+    fn row(id: usize, city: Option<&str>, age: Option<u32>, score: f64) -> Row {
+        Row { id, city: city.map(String::from), age, score }
+    }
+
+    fn keys<'a>() -> Vec<SortKey<'a, Row>> {
+        vec![
+            SortKey::new(|r: &Row| r.city.as_deref(), Direction::Ascending, Nulls::Last),
+            SortKey::new(|r: &Row| r.age.as_ref(), Direction::Descending, Nulls::First),
+            SortKey::new(|r: &Row| Some(&r.score), Direction::Ascending, Nulls::Last),
+        ]
+    }
+
+    #[test]
+    fn sort_by_keys() {
+        let rows = vec![
+            row(0, Some("oslo"), Some(30), 1.5),
+            row(1, None, Some(20), 0.5),
+            row(2, Some("lima"), None, 2.0),
+            row(3, Some("oslo"), Some(40), 1.0),
+            row(4, Some("lima"), Some(25), 3.0),
+            row(5, Some("oslo"), Some(30), 0.5),
+            row(6, None, None, 1.0),
+            row(7, Some("lima"), Some(25), 3.0),
+        ];
+        let sorted = super::sort_by_keys(&rows, &keys());
+        let ids: Vec<usize> = sorted.iter().map(|r| r.id).collect();
+        // lima: missing age first, then age 25 (tied on score, so input order)
+        // oslo: age 40, then age 30 by score
+        // no city: missing age first
+        assert_eq!(ids, vec![2, 4, 7, 3, 5, 0, 6, 1]);
+    }
+
+    #[test]
+    fn matches_repeated_stable_sorts() {
+        // sorting by every key at once is the same as a stable sort by each key on its
+        // own, from the least to the most significant
+        let cities = [None, Some("lima"), Some("oslo"), Some("pune")];
+        let mut rng = StdRng::seed_from_u64(3);
+        let rows: Vec<Row> = (0..300)
+            .map(|id| {
+                let city = cities[rng.gen_range(0..cities.len())];
+                let age = match rng.gen_range(0..5) {
+                    0 => None,
+                    a => Some(a * 10),
+                };
+                row(id, city, age, rng.gen_range(0..4) as f64)
+            })
+            .collect();
+        let keys = keys();
+        let result = super::sort_by_keys(&rows, &keys);
+        let mut expected = rows.clone();
+        for key in keys.iter().rev() {
+            expected = merge::second::sort_by(&expected, |a, b| key.compare(a, b));
+        }
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,68 @@
+//! Shared test helper for the sorts that move elements with raw pointers: checks that a
+//! panic in the middle of sorting neither loses nor duplicates an element.
+
+use std::{cell::Cell, cmp::Ordering, rc::Rc};
+use std::panic::{self, AssertUnwindSafe};
+
+
+/// Compares by `value`, but panics once the shared comparison budget runs out, and
+/// counts how many times it has been dropped.
+#[derive(Debug)]
+pub struct Bomb {
+    value: i32,
+    budget: Rc<Cell<usize>>,
+    drops: Rc<Cell<usize>>,
+}
+
+
+impl PartialEq for Bomb {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+
+impl PartialOrd for Bomb {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let remaining = self.budget.get();
+        if remaining == 0 {
+            panic!("comparison budget exhausted");
+        }
+        self.budget.set(remaining - 1);
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+
+/// Run `sort` with comparison budgets that make it panic at different points, and check
+/// that every value is still there exactly once afterwards, with nothing dropped early.
+pub fn assert_panic_safe(sort: fn(&mut [Bomb])) {
+    let values = [5, 3, 9, 1, 7, 2, 8, 6, 4, 0];
+    for budget in (0..40).step_by(7) {
+        let budget = Rc::new(Cell::new(budget));
+        let drops = Rc::new(Cell::new(0));
+        let mut bombs: Vec<Bomb> = values
+            .iter()
+            .map(|&value| Bomb { value, budget: budget.clone(), drops: drops.clone() })
+            .collect();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            sort(&mut bombs);
+        }));
+        let mut seen: Vec<i32> = bombs.iter().map(|b| b.value).collect();
+        if result.is_ok() {
+            assert!(seen.windows(2).all(|w| w[0] <= w[1]));
+        }
+        seen.sort();
+        assert_eq!(seen, (0..10).collect::<Vec<i32>>());
+        assert_eq!(drops.get(), 0);
+        drop(bombs);
+        assert_eq!(drops.get(), values.len());
+    }
+}
@@ -11,15 +11,35 @@ fn get_pivot_idx(n_values: usize) -> usize {
 }
 
 
-fn sort_inplace<T: Debug + PartialOrd>(values: &mut [T]) {
+pub fn sort_inplace<T: Debug + PartialOrd>(values: &mut [T]) {
+    sort_inplace_by(values, &mut |a: &T, b: &T| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+
+/// Same as `sort_inplace()`, but ordered by `compare`.
+pub fn sort_inplace_by<T, F>(values: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering
+{
     if values.len() < 2 { return }
-    let mut pivot = get_pivot_idx(values.len());
+    let pivot = partition_by(values, get_pivot_idx(values.len()), compare);
+    sort_inplace_by(&mut values[..pivot], compare);
+    sort_inplace_by(&mut values[pivot+1..], compare);
+}
+
+
+/// Move everything that `compare` orders before `values[pivot]` to its left, and
+/// everything ordered after it to its right. Returns the pivot's final index.
+pub fn partition_by<T, F>(values: &mut [T], mut pivot: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering
+{
     let mut i = 0;
     while i < values.len() {
         match i.cmp(&pivot) {
             Ordering::Equal => { i += 1 },
             Ordering::Less => {
-                if values[i] > values[pivot] {
+                if compare(&values[i], &values[pivot]) == Ordering::Greater {
                     values.swap(pivot, pivot-1);
                     if i < pivot - 1{
                         values.swap(pivot, i);
@@ -30,7 +50,7 @@ fn sort_inplace<T: Debug + PartialOrd>(values: &mut [T]) {
                 }
             },
             Ordering::Greater => {
-                if values[i] < values[pivot] {
+                if compare(&values[i], &values[pivot]) == Ordering::Less {
                     values.swap(pivot, pivot + 1);
                     if i > pivot + 1 {
                         values.swap(pivot, i);
@@ -44,8 +64,27 @@ fn sort_inplace<T: Debug + PartialOrd>(values: &mut [T]) {
         }
         //dbg!(&values);
     }
-    sort_inplace(&mut values[..pivot]);
-    sort_inplace(&mut values[pivot+1..]);
+    pivot
+}
+
+
+/// Quickselect: reorder `values` so that `values[k]` is the element that would be there
+/// if `values` were sorted by `compare`, with nothing after it ordered before it, and
+/// nothing before it ordered after it. Takes O(n) time on average.
+pub fn select_by<T, F>(values: &mut [T], k: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering
+{
+    assert!(k < values.len(), "index {k} is out of range for length {}", values.len());
+    let (mut lo, mut hi) = (0, values.len());
+    loop {
+        let pivot = lo + partition_by(&mut values[lo..hi], get_pivot_idx(hi - lo), compare);
+        match k.cmp(&pivot) {
+            Ordering::Equal => return,
+            Ordering::Less => hi = pivot,
+            Ordering::Greater => lo = pivot + 1,
+        }
+    }
 }
 
 
@@ -72,4 +111,20 @@ mod tests {
             assert_eq!(case, expect);
         }
     }
+
+    #[test]
+    fn select_by() {
+        use rand::{seq::SliceRandom, SeedableRng, rngs::StdRng};
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut values: Vec<i64> = (0..500).map(|i| i % 97).collect();
+        let mut sorted = values.clone();
+        sorted.sort();
+        for k in [0, 1, 250, 498, 499] {
+            values.shuffle(&mut rng);
+            super::select_by(&mut values, k, &mut |a: &i64, b: &i64| a.cmp(b));
+            assert_eq!(values[k], sorted[k]);
+            assert!(values[..k].iter().all(|v| *v <= values[k]));
+            assert!(values[k+1..].iter().all(|v| *v >= values[k]));
+        }
+    }
 }
@@ -0,0 +1,283 @@
+//! A k-d tree over points in `D` dimensions, for nearest-neighbour, k-nearest and radius
+//! queries. It's built once from all the points, by splitting on the median along each
+//! axis in turn, found with quickselect (`quick::second::select_by()`), so the tree is
+//! balanced and built in O(n log n) time on average.
+//!
+//! The tree is stored implicitly: the points are reordered so that the node for any range
+//! of them is its middle point, with the left subtree before it and the right one after.
+//! Every point in the left subtree is at or before the node along its axis, and every
+//! point in the right one is at or after it. A query walks down the side the query point
+//! is on first, and only visits the other side when the splitting plane is closer than the
+//! best match found so far.
+
+use std::{cmp::Ordering, fmt::Debug, ops::{Add, Mul, Sub}};
+
+use crate::{error::Error, sorting::quick};
+
+
+/// Floating point coordinates.
+pub trait Coordinate:
+    Copy + PartialOrd + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const INFINITY: Self;
+
+    fn sqrt(self) -> Self;
+
+    fn is_nan(self) -> bool;
+}
+
+
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0.0;
+                const INFINITY: Self = <$t>::INFINITY;
+
+                fn sqrt(self) -> Self {
+                    <$t>::sqrt(self)
+                }
+
+                fn is_nan(self) -> bool {
+                    <$t>::is_nan(self)
+                }
+            }
+        )*
+    };
+}
+
+
+impl_coordinate!(f32, f64);
+
+
+/// A point found by a query: its index in the points the tree was built from, and its
+/// (Euclidean) distance to the query point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbour<T> {
+    pub index: usize,
+    pub distance: T,
+}
+
+
+pub struct KdTree<T, const D: usize> {
+    /// The points, in tree order.
+    points: Vec<[T; D]>,
+    /// `indices[i]`: the index of `points[i]` in the input.
+    indices: Vec<usize>,
+}
+
+
+fn distance_squared<T: Coordinate, const D: usize>(a: &[T; D], b: &[T; D]) -> T {
+    a.iter().zip(b).fold(T::ZERO, |sum, (x, y)| sum + (*x - *y) * (*x - *y))
+}
+
+
+impl<T: Coordinate, const D: usize> KdTree<T, D> {
+    /// Build the tree. Fails if any coordinate is `NaN`, since those can't be ordered.
+    pub fn new(points: &[[T; D]]) -> Result<Self, Error> {
+        assert!(D > 0, "points need at least one dimension");
+        if points.iter().flatten().any(|x| x.is_nan()) {
+            return Err(Error::Incomparable)
+        }
+        let mut nodes: Vec<([T; D], usize)> = points.iter().copied().zip(0..).collect();
+        build(&mut nodes, 0);
+        let (points, indices) = nodes.into_iter().unzip();
+        Ok(Self { points, indices })
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The point closest to `query`, or `None` if the tree is empty.
+    pub fn nearest(&self, query: &[T; D]) -> Option<Neighbour<T>> {
+        let mut best: Option<(T, usize)> = None;
+        self.walk(0, self.len(), 0, query, T::INFINITY, &mut |slot, dist| {
+            if best.is_none_or(|(best_dist, _)| dist < best_dist) {
+                best = Some((dist, slot));
+            }
+            best.map_or(T::INFINITY, |(best_dist, _)| best_dist)
+        });
+        best.map(|(dist, slot)| self.neighbour(slot, dist))
+    }
+
+    /// The `k` points closest to `query` (or all of them, if there are fewer), nearest
+    /// first.
+    pub fn k_nearest(&self, query: &[T; D], k: usize) -> Vec<Neighbour<T>> {
+        if k == 0 {
+            return vec![]
+        }
+        // sorted by distance, so the last one is the one to beat
+        let mut best: Vec<(T, usize)> = Vec::with_capacity(k + 1);
+        self.walk(0, self.len(), 0, query, T::INFINITY, &mut |slot, dist| {
+            if best.len() < k || dist < best[k - 1].0 {
+                let pos = best.partition_point(|(d, _)| *d <= dist);
+                best.insert(pos, (dist, slot));
+                best.truncate(k);
+            }
+            if best.len() < k { T::INFINITY } else { best[k - 1].0 }
+        });
+        best.into_iter().map(|(dist, slot)| self.neighbour(slot, dist)).collect()
+    }
+
+    /// Every point within `radius` of `query` (inclusive), nearest first.
+    pub fn within_radius(&self, query: &[T; D], radius: T) -> Vec<Neighbour<T>> {
+        let max_dist = radius * radius;
+        let mut found: Vec<(T, usize)> = vec![];
+        self.walk(0, self.len(), 0, query, max_dist, &mut |slot, dist| {
+            if dist <= max_dist {
+                found.push((dist, slot));
+            }
+            max_dist
+        });
+        let mut result: Vec<Neighbour<T>> = found
+            .into_iter()
+            .map(|(dist, slot)| self.neighbour(slot, dist))
+            .collect();
+        result.sort_by(|a, b| {
+            a.distance.partial_cmp(&b.distance)
+                .unwrap_or(Ordering::Equal)
+                .then(a.index.cmp(&b.index))
+        });
+        result
+    }
+
+    fn neighbour(&self, slot: usize, dist_squared: T) -> Neighbour<T> {
+        Neighbour { index: self.indices[slot], distance: dist_squared.sqrt() }
+    }
+
+    /// Visit the subtree for `points[lo..hi]`, calling `visit` with each point's position
+    /// and squared distance to `query`. `visit` returns the squared distance beyond which
+    /// points are of no more interest, so subtrees that lie entirely beyond it are skipped.
+    /// Returns that distance, after the last visit.
+    fn walk<F>(&self, lo: usize, hi: usize, depth: usize, query: &[T; D], mut max_dist: T, visit: &mut F) -> T
+    where
+        F: FnMut(usize, T) -> T
+    {
+        if lo >= hi {
+            return max_dist
+        }
+        let mid = lo + (hi - lo) / 2;
+        let point = &self.points[mid];
+        max_dist = visit(mid, distance_squared(point, query));
+        let axis = depth % D;
+        let diff = query[axis] - point[axis];
+        let (near, far) = if diff < T::ZERO {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        max_dist = self.walk(near.0, near.1, depth + 1, query, max_dist, visit);
+        if diff * diff <= max_dist {
+            max_dist = self.walk(far.0, far.1, depth + 1, query, max_dist, visit);
+        }
+        max_dist
+    }
+}
+
+
+/// Arrange `nodes` as an implicit subtree, splitting on the axis for `depth`.
+fn build<T: Coordinate, const D: usize>(nodes: &mut [([T; D], usize)], depth: usize) {
+    if nodes.len() < 2 {
+        return
+    }
+    let axis = depth % D;
+    let mid = nodes.len() / 2;
+    quick::second::select_by(nodes, mid, &mut |a: &([T; D], usize), b: &([T; D], usize)| {
+        // `NaN`s were rejected up front
+        a.0[axis].partial_cmp(&b.0[axis]).unwrap_or(Ordering::Equal)
+    });
+    let (left, right) = nodes.split_at_mut(mid);
+    build(left, depth + 1);
+    build(&mut right[1..], depth + 1);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::error::Error;
+    use super::{KdTree, Neighbour};
+
+    /// Every point, nearest first, by brute force.
+    fn by_distance<const D: usize>(points: &[[f64; D]], query: &[f64; D]) -> Vec<Neighbour<f64>> {
+        let mut all: Vec<Neighbour<f64>> = points
+            .iter()
+            .enumerate()
+            .map(|(index, p)| Neighbour { index, distance: super::distance_squared(p, query).sqrt() })
+            .collect();
+        all.sort_by(|a, b| a.distance.total_cmp(&b.distance).then(a.index.cmp(&b.index)));
+        all
+    }
+
+    fn distances(neighbours: &[Neighbour<f64>]) -> Vec<f64> {
+        neighbours.iter().map(|n| n.distance).collect()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(17);
+        let points: Vec<[f64; 3]> = (0..500)
+            .map(|_| [rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)])
+            .collect();
+        let tree = KdTree::new(&points).unwrap();
+        assert_eq!(tree.len(), 500);
+        for _ in 0..50 {
+            let query = [rng.gen_range(-1.5..1.5), rng.gen_range(-1.5..1.5), rng.gen_range(-1.5..1.5)];
+            let expected = by_distance(&points, &query);
+            assert_eq!(tree.nearest(&query), Some(expected[0]));
+            assert_eq!(distances(&tree.k_nearest(&query, 7)), distances(&expected[..7]));
+            let within: Vec<Neighbour<f64>> =
+                expected.iter().copied().take_while(|n| n.distance <= 0.4).collect();
+            assert_eq!(tree.within_radius(&query, 0.4), within);
+        }
+    }
+
+    #[test]
+    fn duplicates_and_ties() {
+        // a grid with every point repeated, so there are many equal coordinates and
+        // distances
+        let mut points: Vec<[f32; 2]> = vec![];
+        for x in 0..10 {
+            for y in 0..10 {
+                points.push([x as f32, y as f32]);
+                points.push([x as f32, y as f32]);
+            }
+        }
+        let tree = KdTree::new(&points).unwrap();
+        let nearest = tree.nearest(&[3.0, 4.0]).unwrap();
+        assert_eq!(nearest.distance, 0.0);
+        assert_eq!(points[nearest.index], [3.0, 4.0]);
+        // the point itself twice, then its 4 neighbours twice each
+        let k_nearest = tree.k_nearest(&[3.0, 4.0], 10);
+        let dists: Vec<f32> = k_nearest.iter().map(|n| n.distance).collect();
+        assert_eq!(dists, vec![0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0]);
+        let mut within: Vec<usize> = tree.within_radius(&[0.5, 0.5], 0.75)
+            .iter()
+            .map(|n| n.index)
+            .collect();
+        within.sort();
+        assert_eq!(within, vec![0, 1, 2, 3, 20, 21, 22, 23]);
+        // asking for more than there are returns everything
+        assert_eq!(tree.k_nearest(&[0.0, 0.0], 1000).len(), 200);
+    }
+
+    #[test]
+    fn edge_cases() {
+        let empty: KdTree<f64, 2> = KdTree::new(&[]).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.nearest(&[0.0, 0.0]), None);
+        assert!(empty.k_nearest(&[0.0, 0.0], 3).is_empty());
+        assert!(empty.within_radius(&[0.0, 0.0], 1.0).is_empty());
+        let tree = KdTree::new(&[[1.0_f64, 2.0]]).unwrap();
+        assert!(tree.k_nearest(&[0.0, 0.0], 0).is_empty());
+        assert_eq!(tree.nearest(&[4.0, 6.0]), Some(Neighbour { index: 0, distance: 5.0 }));
+        assert!(matches!(KdTree::new(&[[0.0, f64::NAN]]), Err(Error::Incomparable)));
+    }
+}
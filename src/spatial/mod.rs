@@ -0,0 +1,4 @@
+//! Spatial indexes, for finding points by their position rather than by a key, e.g. to
+//! connect each point to its neighbours when building a Euclidean graph for clustering.
+
+pub mod kd_tree;
@@ -0,0 +1,190 @@
+//! A disjoint set that can be shared between threads, e.g. for a parallel Boruvka or
+//! connected components pass where every thread unites the edges of its own chunk.
+//! It's lock-free: parents are atomics, and a root is only ever linked under another with
+//! a compare-and-swap that fails if it stopped being a root in the meantime, in which case
+//! the union starts over. `find()` does path halving, also with compare-and-swaps, which
+//! only ever move a node's parent further up its own tree, so they are safe to lose.
+//! Roots are linked by index, the smaller under the larger, since ranks or sizes can't be
+//! updated atomically together with the parent. Path halving keeps the trees shallow in
+//! practice.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+
+#[derive(Debug)]
+pub struct ConcurrentDisjointSet {
+    parents: Vec<AtomicUsize>,
+    count: AtomicUsize,
+}
+
+
+impl ConcurrentDisjointSet {
+    /// `len` elements, each in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).map(AtomicUsize::new).collect(),
+            count: AtomicUsize::new(len),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    /// The representative of the set containing `x`, at some point during the call.
+    /// Panics if `x >= len()`.
+    pub fn find(&self, x: usize) -> usize {
+        let mut x = x;
+        loop {
+            let parent = self.parents[x].load(Ordering::Acquire);
+            if parent == x {
+                return x
+            }
+            let grandparent = self.parents[parent].load(Ordering::Acquire);
+            if parent != grandparent {
+                // another thread may have changed it already, which is fine either way
+                let _ = self.parents[x].compare_exchange_weak(
+                    parent, grandparent, Ordering::AcqRel, Ordering::Acquire,
+                );
+            }
+            x = grandparent;
+        }
+    }
+
+    /// Merge the sets containing `a` and `b`. Returns `false` if they already were the
+    /// same set. Of several threads that race to merge the same two sets, exactly one
+    /// gets `true`.
+    pub fn union(&self, a: usize, b: usize) -> bool {
+        loop {
+            let root_a = self.find(a);
+            let root_b = self.find(b);
+            if root_a == root_b {
+                return false
+            }
+            let (child, root) = if root_a < root_b { (root_a, root_b) } else { (root_b, root_a) };
+            let linked = self.parents[child]
+                .compare_exchange(child, root, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok();
+            if linked {
+                self.count.fetch_sub(1, Ordering::AcqRel);
+                return true
+            }
+        }
+    }
+
+    /// Whether `a` and `b` are in the same set. Only `false` if they were in different
+    /// sets at some point during the call.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        loop {
+            let root_a = self.find(a);
+            let root_b = self.find(b);
+            if root_a == root_b {
+                return true
+            }
+            // otherwise `root_a` may have been linked under `root_b` since it was found
+            if self.parents[root_a].load(Ordering::Acquire) == root_a {
+                return false
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::{AtomicUsize, Ordering}, thread};
+
+    use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+    use crate::union_find::DisjointSet;
+    use super::ConcurrentDisjointSet;
+
+    const THREADS: usize = 8;
+
+    fn random_edges(n: usize, len: usize, seed: u64) -> Vec<(usize, usize)> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n))).collect()
+    }
+
+    #[test]
+    fn union_find() {
+        let ds = ConcurrentDisjointSet::new(6);
+        assert!(ds.union(0, 1));
+        assert!(ds.union(4, 1));
+        assert!(!ds.union(0, 4));
+        assert!(ds.connected(4, 0));
+        assert!(!ds.connected(2, 0));
+        assert_eq!(ds.count(), 4);
+        assert_eq!(ds.find(0), 4);
+    }
+
+    #[test]
+    fn parallel_unions() {
+        let n = 10_000;
+        let edges = random_edges(n, 8_000, 73);
+        let ds = ConcurrentDisjointSet::new(n);
+        let merges = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for chunk in edges.chunks(edges.len().div_ceil(THREADS)) {
+                let (ds, merges) = (&ds, &merges);
+                scope.spawn(move || {
+                    for (a, b) in chunk {
+                        if ds.union(*a, *b) {
+                            merges.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+        let mut expected = DisjointSet::new(n);
+        for (a, b) in &edges {
+            expected.union(*a, *b);
+        }
+        assert_eq!(ds.count(), expected.count());
+        assert_eq!(merges.load(Ordering::Relaxed), n - expected.count());
+        for x in 0..n {
+            assert_eq!(ds.find(x), ds.find(expected.find(x)));
+        }
+    }
+
+    #[test]
+    fn racing_on_the_same_edges() {
+        // every thread unites the same edges, so each merge has to be won exactly once
+        let n = 2_000;
+        let edges = random_edges(n, 3_000, 79);
+        let ds = ConcurrentDisjointSet::new(n);
+        let merges = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for t in 0..THREADS {
+                let (ds, edges, merges) = (&ds, &edges, &merges);
+                scope.spawn(move || {
+                    // in a different order on each thread
+                    let mut edges = edges.clone();
+                    edges.shuffle(&mut StdRng::seed_from_u64(t as u64));
+                    for (a, b) in edges {
+                        if ds.union(a, b) {
+                            merges.fetch_add(1, Ordering::Relaxed);
+                        }
+                        // whatever has been merged stays merged
+                        assert!(ds.connected(a, b));
+                    }
+                });
+            }
+        });
+        let mut expected = DisjointSet::new(n);
+        for (a, b) in &edges {
+            expected.union(*a, *b);
+        }
+        assert_eq!(merges.load(Ordering::Relaxed), n - expected.count());
+        assert_eq!(ds.count(), expected.count());
+    }
+}
@@ -0,0 +1,412 @@
+//! The disjoint set (union-find) shared by the rest of the crate, over the elements
+//! `0..len()`. By default it uses union by size and full path compression, but both
+//! strategies can be chosen with `DisjointSet::with_strategy()`:
+//! - `Union::ByRank` / `Union::BySize`: attach the root of the shallower (by an upper
+//!   bound on height) or smaller tree under the other one. Either keeps trees O(log n)
+//!   deep on their own.
+//! - `Compression::Full`: point every node on the path to the root straight at the root.
+//!   Needs two passes over the path.
+//! - `Compression::Halving`: point every other node on the path at its grandparent.
+//! - `Compression::Splitting`: point every node on the path at its grandparent.
+//! - `Compression::None`: leave the trees as they are, e.g. so unions can be undone.
+//!
+//! Any of the compressions, combined with either union, gives the same O(α(n)) amortized
+//! bound; the differences are in constant factors, which the `#[ignore]`d `benchmark` test
+//! below compares.
+//!
+//! Each set also keeps its members in a circular linked list, so that they can be listed
+//! in time proportional to the size of the set, rather than of the whole structure.
+
+use std::iter::FusedIterator;
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Union {
+    ByRank,
+    #[default]
+    BySize,
+}
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    Full,
+    Halving,
+    Splitting,
+    None,
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct DisjointSet {
+    union: Union,
+    compression: Compression,
+    parents: Vec<usize>,
+    /// `ranks[root]`: an upper bound on the height of the tree of `root`. Only kept with
+    /// `Union::ByRank`, and empty otherwise.
+    ranks: Vec<u8>,
+    /// `sizes[root]`: the number of elements in the set of `root`. Not kept up to date for
+    /// elements that aren't roots. Only kept with `Union::BySize`, and empty otherwise.
+    sizes: Vec<usize>,
+    /// `next[x]`: the member after `x` in the circular list of its set.
+    next: Vec<usize>,
+    count: usize,
+}
+
+
+impl DisjointSet {
+    /// `len` elements, each in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self::with_strategy(len, Union::default(), Compression::default())
+    }
+
+    pub fn with_strategy(len: usize, union: Union, compression: Compression) -> Self {
+        Self {
+            union,
+            compression,
+            parents: (0..len).collect(),
+            ranks: if union == Union::ByRank { vec![0; len] } else { vec![] },
+            sizes: if union == Union::BySize { vec![1; len] } else { vec![] },
+            next: (0..len).collect(),
+            count: len,
+        }
+    }
+
+    /// Add a new element in a set of its own, and return it.
+    pub fn make_set(&mut self) -> usize {
+        let x = self.parents.len();
+        self.parents.push(x);
+        match self.union {
+            Union::ByRank => self.ranks.push(0),
+            Union::BySize => self.sizes.push(1),
+        }
+        self.next.push(x);
+        self.count += 1;
+        x
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The representative of the set containing `x`. Panics if `x >= len()`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut x = x;
+        match self.compression {
+            Compression::Full => {
+                let mut root = x;
+                while self.parents[root] != root {
+                    root = self.parents[root];
+                }
+                while self.parents[x] != root {
+                    let parent = self.parents[x];
+                    self.parents[x] = root;
+                    x = parent;
+                }
+                root
+            },
+            Compression::Halving => {
+                while self.parents[x] != x {
+                    let grandparent = self.parents[self.parents[x]];
+                    self.parents[x] = grandparent;
+                    x = grandparent;
+                }
+                x
+            },
+            Compression::Splitting => {
+                while self.parents[x] != x {
+                    let parent = self.parents[x];
+                    self.parents[x] = self.parents[parent];
+                    x = parent;
+                }
+                x
+            },
+            Compression::None => {
+                while self.parents[x] != x {
+                    x = self.parents[x];
+                }
+                x
+            },
+        }
+    }
+
+    /// Merge the sets containing `a` and `b`. Returns `false` if they already were the
+    /// same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let mut root_a = self.find(a);
+        let mut root_b = self.find(b);
+        if root_a == root_b {
+            return false
+        }
+        match self.union {
+            Union::ByRank => {
+                if self.ranks[root_a] < self.ranks[root_b] {
+                    std::mem::swap(&mut root_a, &mut root_b);
+                }
+                if self.ranks[root_a] == self.ranks[root_b] {
+                    self.ranks[root_a] += 1;
+                }
+            },
+            Union::BySize => {
+                if self.sizes[root_a] < self.sizes[root_b] {
+                    std::mem::swap(&mut root_a, &mut root_b);
+                }
+                self.sizes[root_a] += self.sizes[root_b];
+            },
+        }
+        self.parents[root_b] = root_a;
+        // splice the two circular lists into one
+        self.next.swap(root_a, root_b);
+        self.count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of elements in the set containing `x`. Takes O(1) time with
+    /// `Union::BySize`, but O(size) with `Union::ByRank`, which doesn't keep sizes.
+    pub fn size(&mut self, x: usize) -> usize {
+        match self.union {
+            Union::ByRank => self.members(x).count(),
+            Union::BySize => {
+                let root = self.find(x);
+                self.sizes[root]
+            },
+        }
+    }
+
+    /// The elements in the same set as `x`, starting with `x` itself.
+    pub fn members(&self, x: usize) -> Members<'_> {
+        assert!(x < self.len(), "element {x} is out of range for length {}", self.len());
+        Members { next: &self.next, start: x, current: Some(x) }
+    }
+
+    /// Every set, each sorted, and ordered by their smallest elements.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        // `group_of[root]`: the index in `groups` of the set of `root`
+        let mut group_of = vec![usize::MAX; self.len()];
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.count);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            if group_of[root] == usize::MAX {
+                group_of[root] = groups.len();
+                groups.push(vec![]);
+            }
+            groups[group_of[root]].push(x);
+        }
+        groups
+    }
+}
+
+
+/// Iterator over the members of one set, returned by `DisjointSet::members()`.
+pub struct Members<'a> {
+    next: &'a [usize],
+    start: usize,
+    current: Option<usize>,
+}
+
+
+impl Iterator for Members<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let x = self.current?;
+        let next = self.next[x];
+        self.current = if next == self.start { None } else { Some(next) };
+        Some(x)
+    }
+}
+
+
+impl FusedIterator for Members<'_> {}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+    use super::{Compression, DisjointSet, Union};
+
+    const STRATEGIES: [(Union, Compression); 8] = [
+        (Union::ByRank, Compression::Full),
+        (Union::ByRank, Compression::Halving),
+        (Union::ByRank, Compression::Splitting),
+        (Union::ByRank, Compression::None),
+        (Union::BySize, Compression::Full),
+        (Union::BySize, Compression::Halving),
+        (Union::BySize, Compression::Splitting),
+        (Union::BySize, Compression::None),
+    ];
+
+    #[test]
+    fn union_find() {
+        let mut ds = DisjointSet::new(8);
+        assert_eq!(ds.count(), 8);
+        assert!(ds.union(0, 1));
+        assert!(ds.union(2, 3));
+        assert!(ds.union(1, 3));
+        assert!(!ds.union(0, 2));
+        assert!(ds.union(5, 6));
+        assert_eq!(ds.count(), 4);
+        assert!(ds.connected(0, 2));
+        assert!(!ds.connected(0, 5));
+        assert_eq!(ds.size(3), 4);
+        assert_eq!(ds.size(4), 1);
+        let mut members: Vec<usize> = ds.members(2).collect();
+        assert_eq!(members[0], 2);
+        members.sort();
+        assert_eq!(members, vec![0, 1, 2, 3]);
+        assert_eq!(ds.members(7).collect::<Vec<_>>(), vec![7]);
+        assert_eq!(ds.groups(), vec![vec![0, 1, 2, 3], vec![4], vec![5, 6], vec![7]]);
+        let x = ds.make_set();
+        assert_eq!((x, ds.len(), ds.count()), (8, 9, 5));
+        assert!(ds.union(x, 4));
+        assert_eq!(ds.groups()[1], vec![4, 8]);
+    }
+
+    #[test]
+    fn matches_labels() {
+        // compare with relabelling every element of one set on each union
+        let n = 300;
+        let mut rng = StdRng::seed_from_u64(41);
+        let mut ds = DisjointSet::new(n);
+        let mut labels: Vec<usize> = (0..n).collect();
+        for _ in 0..400 {
+            let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            let (label_a, label_b) = (labels[a], labels[b]);
+            assert_eq!(ds.union(a, b), label_a != label_b);
+            for label in labels.iter_mut() {
+                if *label == label_b { *label = label_a }
+            }
+            let c = rng.gen_range(0..n);
+            let mut members: Vec<usize> = ds.members(c).collect();
+            members.sort();
+            let expected: Vec<usize> = (0..n).filter(|x| labels[*x] == labels[c]).collect();
+            assert_eq!(ds.size(c), expected.len());
+            assert_eq!(members, expected);
+        }
+        let mut distinct = labels.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(ds.count(), distinct.len());
+        assert_eq!(ds.groups().len(), distinct.len());
+    }
+
+    #[test]
+    fn strategies_agree() {
+        let n = 500;
+        let mut rng = StdRng::seed_from_u64(43);
+        let ops: Vec<(usize, usize)> =
+            (0..700).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n))).collect();
+        let mut expected = DisjointSet::new(n);
+        let merged: Vec<bool> = ops.iter().map(|(a, b)| expected.union(*a, *b)).collect();
+        for (union, compression) in STRATEGIES {
+            let mut ds = DisjointSet::with_strategy(n, union, compression);
+            for ((a, b), merge) in ops.iter().zip(&merged) {
+                assert_eq!(ds.union(*a, *b), *merge, "{union:?} {compression:?}");
+            }
+            assert_eq!(ds.groups(), expected.groups(), "{union:?} {compression:?}");
+        }
+    }
+
+    /// Union sequences that are hard on some strategy, as `(name, unions)`.
+    fn adversarial(n: usize) -> Vec<(&'static str, Vec<(usize, usize)>)> {
+        let mut rng = StdRng::seed_from_u64(47);
+        // each union adds a new element under the growing set: long paths without
+        // union by size or rank
+        let chain: Vec<(usize, usize)> = (1..n).map(|i| (i, i - 1)).collect();
+        // merge sets of equal size pairwise, building binomial trees of the greatest
+        // possible height, log(n)
+        let mut binomial = vec![];
+        let mut width = 1;
+        while width < n {
+            binomial.extend((0..n - width).step_by(2 * width).map(|i| (i + width, i)));
+            width *= 2;
+        }
+        let mut random: Vec<(usize, usize)> =
+            (0..2 * n).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n))).collect();
+        random.shuffle(&mut rng);
+        vec![("chain", chain), ("binomial", binomial), ("random", random)]
+    }
+
+    /// Compare the strategies' running times. Run with
+    /// `cargo test --release -- --ignored --nocapture benchmark`.
+    #[test]
+    #[ignore]
+    fn benchmark() {
+        let n = 1 << 20;
+        let mut rng = StdRng::seed_from_u64(53);
+        let queries: Vec<usize> = (0..4 * n).map(|_| rng.gen_range(0..n)).collect();
+        for (name, unions) in adversarial(n) {
+            for (union, compression) in STRATEGIES {
+                let start = Instant::now();
+                let mut ds = DisjointSet::with_strategy(n, union, compression);
+                for (a, b) in &unions {
+                    ds.union(*a, *b);
+                }
+                // the deepest nodes of the binomial trees are the odd ones
+                let found: usize = queries.iter().map(|x| ds.find(*x | 1)).sum();
+                println!(
+                    "{name:>8} {:<24} {:>8.1?} ({found})",
+                    format!("{union:?}/{compression:?}"),
+                    start.elapsed(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rank_and_size_pick_different_roots() {
+        for compression in [Compression::Full, Compression::Halving, Compression::Splitting, Compression::None] {
+            let mut by_rank = DisjointSet::with_strategy(9, Union::ByRank, compression);
+            let mut by_size = DisjointSet::with_strategy(9, Union::BySize, compression);
+            for ds in [&mut by_rank, &mut by_size] {
+                // a star of 5 elements: rank 1, rooted at 0
+                for x in 1..5 {
+                    ds.union(0, x);
+                }
+                // a binomial tree of 4 elements: rank 2, rooted at 5
+                ds.union(5, 6);
+                ds.union(7, 8);
+                ds.union(5, 7);
+                assert_eq!((ds.find(4), ds.find(8)), (0, 5));
+                ds.union(0, 5);
+                assert_eq!(ds.size(3), 9);
+            }
+            // the taller tree wins by rank, the bigger one by size
+            assert_eq!(by_rank.find(0), 5, "{compression:?}");
+            assert_eq!(by_size.find(5), 0, "{compression:?}");
+        }
+    }
+
+    #[test]
+    fn find_long_chain() {
+        // union by rank or size never builds a chain, so link the parents by hand. `find`
+        // has to walk it without recursing, whatever the compression
+        let n = 1_000_000;
+        for compression in [Compression::Full, Compression::Halving, Compression::Splitting, Compression::None] {
+            let mut ds = DisjointSet::with_strategy(n, Union::BySize, compression);
+            for i in 0..n - 1 {
+                ds.parents[i] = i + 1;
+            }
+            assert_eq!(ds.find(0), n - 1, "{compression:?}");
+            assert_eq!(ds.find(n / 2), n - 1, "{compression:?}");
+        }
+    }
+}
@@ -1,22 +1,62 @@
-//! An implementation of the union-find algorithm, contained within a struct.
+//! An implementation of the union-find algorithm, contained within a struct, over keys
+//! of any hashable type. The sets themselves are kept by the shared, index-based
+//! `union_find::DisjointSet`; this only maps keys to indices and back.
 
 use std::{collections::HashMap, hash::Hash};
 
+use crate::{error::Error, union_find::DisjointSet};
 
+
+/// A disjoint set over values of any hashable type `T`, the keys. Every key added with
+/// `make_set()` is also given an index, in the order they were added, and every operation
+/// comes in two flavours: one that takes keys, and one (suffixed `_idx`) that takes
+/// indices, for callers that already keep track of those.
 #[derive(Debug)]
-pub struct DisjointSet<T: Clone + Eq + Hash> {
-    parents: Vec<usize>,
-    ranks: Vec<usize>,
+pub struct KeyedDisjointSet<T: Clone + Eq + Hash> {
+    ds: DisjointSet,
+    /// `keys[idx]`: the key with index `idx`.
+    keys: Vec<T>,
     map: HashMap<T, usize>
 }
 
 
-impl<T: Clone + Eq + Hash> DisjointSet<T> {
+impl<T: Clone + Eq + Hash> Default for KeyedDisjointSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+impl<T: Clone + Eq + Hash> KeyedDisjointSet<T> {
     pub fn new() -> Self {
-        let parents = vec![];
-        let ranks = vec![];
+        let ds = DisjointSet::new(0);
+        let keys = vec![];
         let map = HashMap::new();
-        Self { parents, ranks, map }
+        Self { ds, keys, map }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.ds.count()
+    }
+
+    /// Add `value` in a set of its own, unless it was already added, and return its index.
+    pub fn make_set(&mut self, value: &T) -> usize {
+        if let Some(idx) = self.get_idx(value) {
+            return idx
+        }
+        let idx = self.ds.make_set();
+        self.keys.push(value.clone());
+        self.map.insert(value.clone(), idx);
+        idx
     }
 
     pub fn get_idx(&self, value: &T) -> Option<usize> {
@@ -26,71 +66,82 @@ impl<T: Clone + Eq + Hash> DisjointSet<T> {
         None
     }
 
-    pub fn find(&mut self, idx: usize) -> Option<usize> {
-        //if idx >= self.parents.len() { return None }
-        if self.parents[idx] != idx {
-            self.parents[idx] = self.find(self.parents[idx])?;
-        }
-        Some(self.parents[idx])
+    pub fn key(&self, idx: usize) -> Option<&T> {
+        self.keys.get(idx)
     }
 
-    pub fn make_set(&mut self, value: &T) {
-        if !self.map.contains_key(value) {
-            let idx = self.parents.len();
-            self.parents.push(idx);
-            self.ranks.push(0);
-            self.map.insert(value.clone(), idx);
-        }
+    fn idx_of(&self, value: &T) -> Result<usize, Error> {
+        self.get_idx(value).ok_or(Error::UnknownVertex)
     }
-    
-    pub fn union(&mut self, a: &T, b: &T) -> Result<(), String> {
-        let idx_a = match self.get_idx(a) {
-            Some(i) => i,
-            None => return Err(String::from("`a` not found"))
-        };
-        let idx_b = match self.get_idx(b) {
-            Some(i) => i,
-            None => return Err(String::from("`b` not found"))
-        };
-        // these should always be found if keys were in map
-        let mut root_a = self.find(idx_a).expect("found `a` in map but not parents");
-        let mut root_b = self.find(idx_b).expect("found `b` in map but not parents");
-        if root_a == root_b { return Ok(()) }
-        if self.ranks[root_a] < self.ranks[root_b] {
-            std::mem::swap(&mut root_a, &mut root_b)
-        }
-        self.parents[root_b] = root_a;
-        if self.ranks[root_a] == self.ranks[root_b] {
-            self.ranks[root_a] += 1;
+
+    fn check_idx(&self, idx: usize) -> Result<usize, Error> {
+        let len = self.len();
+        if idx >= len {
+            return Err(Error::IndexOutOfRange { index: idx, len })
         }
-        Ok(())
+        Ok(idx)
+    }
+
+    /// The representative key of the set containing `value`.
+    pub fn find(&mut self, value: &T) -> Result<&T, Error> {
+        let root = self.ds.find(self.idx_of(value)?);
+        Ok(&self.keys[root])
+    }
+
+    /// Merge the sets containing `a` and `b`. Returns `false` if they already were the
+    /// same set.
+    pub fn union(&mut self, a: &T, b: &T) -> Result<bool, Error> {
+        let (idx_a, idx_b) = (self.idx_of(a)?, self.idx_of(b)?);
+        Ok(self.ds.union(idx_a, idx_b))
+    }
+
+    pub fn connected(&mut self, a: &T, b: &T) -> Result<bool, Error> {
+        let (idx_a, idx_b) = (self.idx_of(a)?, self.idx_of(b)?);
+        Ok(self.ds.connected(idx_a, idx_b))
+    }
+
+    /// The index of the representative of the set containing the element at `idx`.
+    pub fn find_idx(&mut self, idx: usize) -> Result<usize, Error> {
+        let idx = self.check_idx(idx)?;
+        Ok(self.ds.find(idx))
+    }
+
+    /// Same as `union()`, by index.
+    pub fn union_idx(&mut self, a: usize, b: usize) -> Result<bool, Error> {
+        let (a, b) = (self.check_idx(a)?, self.check_idx(b)?);
+        Ok(self.ds.union(a, b))
+    }
+
+    /// Same as `connected()`, by index.
+    pub fn connected_idx(&mut self, a: usize, b: usize) -> Result<bool, Error> {
+        let (a, b) = (self.check_idx(a)?, self.check_idx(b)?);
+        Ok(self.ds.connected(a, b))
     }
 }
 
 
-pub struct Graph {
-    pub edges: Vec<(usize, usize, f32)>,
-    pub vertices: Vec<usize>,
+pub struct Graph<T = usize> {
+    pub edges: Vec<(T, T, f32)>,
+    pub vertices: Vec<T>,
 }
 
 
-pub fn build_mst(graph: Graph) -> Vec<(usize, usize)> {
+/// Kruskal's algorithm, on vertices with any labels. Fails if an edge has a vertex that
+/// isn't in `vertices`.
+pub fn build_mst<T: Clone + Eq + Hash>(graph: Graph<T>) -> Result<Vec<(T, T)>, Error> {
     let mut mst = vec![];
-    let mut ds = DisjointSet::<usize>::new();
-    for v in graph.vertices {
-        ds.make_set(&v);
+    let mut ds = KeyedDisjointSet::<T>::new();
+    for v in &graph.vertices {
+        ds.make_set(v);
     }
-    let mut sorted_edges: Vec<&(usize, usize, f32)> = graph.edges.iter().collect();
+    let mut sorted_edges: Vec<&(T, T, f32)> = graph.edges.iter().collect();
     sorted_edges.sort_unstable_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
     for edge in sorted_edges {
-        let root_a = ds.find(edge.0).unwrap();
-        let root_b = ds.find(edge.1).unwrap();
-        if root_a != root_b {
-            mst.push((edge.0, edge.1));
-            ds.union(&root_a, &root_b).unwrap();
+        if ds.union(&edge.0, &edge.1)? {
+            mst.push((edge.0.clone(), edge.1.clone()));
         }
     }
-    mst
+    Ok(mst)
 }
 
 
@@ -116,7 +167,7 @@ mod tests {
             edges,
             vertices
         };
-        let mut mst = super::build_mst(graph);
+        let mut mst = super::build_mst(graph).unwrap();
         mst.sort_by_key(|e| (e.0, e.1));
         let expected_mst = [
             (0, 1),
@@ -162,7 +213,7 @@ mod tests {
             edges,
             vertices
         };
-        let mut mst = super::build_mst(graph);
+        let mut mst = super::build_mst(graph).unwrap();
         mst.sort_by_key(|e| (e.0, e.1));
         let expected_mst = [
             // mst 1
@@ -182,4 +233,63 @@ mod tests {
         ];
         assert_eq!(mst, expected_mst);
     }
+
+    #[test]
+    fn keyed_and_indexed() {
+        use crate::error::Error;
+        let mut ds = super::KeyedDisjointSet::<String>::new();
+        let names = ["ash", "birch", "cedar", "elm"];
+        for name in names {
+            ds.make_set(&name.to_string());
+        }
+        assert_eq!(ds.make_set(&"cedar".to_string()), 2);
+        let key = |name: &str| name.to_string();
+        assert_eq!(ds.union(&key("elm"), &key("ash")), Ok(true));
+        assert_eq!(ds.union_idx(2, 1), Ok(true));
+        assert_eq!(ds.union(&key("ash"), &key("elm")), Ok(false));
+        assert_eq!(ds.connected(&key("ash"), &key("elm")), Ok(true));
+        assert_eq!(ds.connected(&key("ash"), &key("cedar")), Ok(false));
+        assert_eq!(ds.connected_idx(1, 2), Ok(true));
+        let root = ds.find(&key("ash")).unwrap().clone();
+        assert!(root == "ash" || root == "elm");
+        let root_idx = ds.find_idx(3).unwrap();
+        assert_eq!(ds.key(root_idx), Some(&root));
+        assert_eq!(ds.count(), 2);
+        assert_eq!(ds.find_idx(4), Err(Error::IndexOutOfRange { index: 4, len: 4 }));
+        assert_eq!(ds.union(&key("oak"), &key("ash")), Err(Error::UnknownVertex));
+        assert_eq!(ds.union_idx(0, 4), Err(Error::IndexOutOfRange { index: 4, len: 4 }));
+    }
+
+    #[test]
+    fn kruskals_string_labels() {
+        // the same graph as `kruskals()`, with labels that aren't indices, added in a
+        // different order
+        let labels = ["a", "b", "c", "d", "e", "f", "g"];
+        let vertices: Vec<String> = labels.iter().rev().map(|l| l.to_string()).collect();
+        let edges = vec![
+            (0, 1, 7.),
+            (0, 3, 5.),
+            (1, 2, 8.),
+            (1, 3, 9.),
+            (1, 4, 7.),
+            (2, 4, 5.),
+            (3, 4, 15.),
+            (3, 5, 6.),
+            (4, 5, 8.),
+            (4, 6, 9.),
+            (5, 6, 11.),
+        ];
+        let edges = edges
+            .into_iter()
+            .map(|(u, v, w)| (labels[u].to_string(), labels[v].to_string(), w))
+            .collect();
+        let graph = super::Graph { edges, vertices };
+        let mut mst = super::build_mst(graph).unwrap();
+        mst.sort();
+        let expected_mst = [("a", "b"), ("a", "d"), ("b", "e"), ("c", "e"), ("d", "f"), ("e", "g")]
+            .map(|(u, v)| (u.to_string(), v.to_string()));
+        assert_eq!(mst, expected_mst);
+        let graph = super::Graph { edges: vec![("a", "z", 1.)], vertices: vec!["a"] };
+        assert_eq!(super::build_mst(graph), Err(crate::error::Error::UnknownVertex));
+    }
 }
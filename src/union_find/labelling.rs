@@ -0,0 +1,255 @@
+//! Connected-component labelling of 2D grids, e.g. images or raster masks, with the
+//! classic two passes over the grid:
+//! 1. In raster order, unite each cell with those of its neighbours that were already
+//!    visited (to the left and above) and belong to the same region.
+//! 2. Number the sets in the order their first cell appears, and collect each one's size
+//!    and bounding box.
+//!
+//! Grids are stored row-major in a slice, `width` cells per row.
+
+use crate::union_find::DisjointSet;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Cells that share an edge are neighbours.
+    Four,
+    /// Cells that share an edge or a corner are neighbours.
+    Eight,
+}
+
+
+/// The smallest rectangle holding a component, with inclusive bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min_row: usize,
+    pub min_col: usize,
+    pub max_row: usize,
+    pub max_col: usize,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// The number of cells.
+    pub size: usize,
+    pub bounds: BoundingBox,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Labels {
+    pub width: usize,
+    pub height: usize,
+    /// `labels[row * width + col]`: the index in `components` of the cell's component, or
+    /// `None` for background cells.
+    pub labels: Vec<Option<usize>>,
+    pub components: Vec<Component>,
+}
+
+
+impl Labels {
+    pub fn get(&self, row: usize, col: usize) -> Option<usize> {
+        assert!(row < self.height && col < self.width, "cell ({row}, {col}) is out of range");
+        self.labels[row * self.width + col]
+    }
+}
+
+
+/// Label the regions of `grid`, where neighbouring cells `a` and `b` are in the same
+/// region when `same(a, b)`. Every cell gets a label.
+pub fn label<T, F>(grid: &[T], width: usize, connectivity: Connectivity, same: F) -> Labels
+where
+    F: Fn(&T, &T) -> bool
+{
+    label_foreground(grid, width, connectivity, |_| true, same)
+}
+
+
+/// Label the `true` cells of `mask`, where neighbouring `true` cells are in the same
+/// component. `false` cells are background.
+pub fn label_mask(mask: &[bool], width: usize, connectivity: Connectivity) -> Labels {
+    label_foreground(mask, width, connectivity, |cell| *cell, |_, _| true)
+}
+
+
+/// Label the regions of the cells of `grid` for which `foreground` is true, where
+/// neighbouring cells `a` and `b` are in the same region when `same(a, b)`. The rest are
+/// background, and get no label.
+pub fn label_foreground<T, P, F>(
+    grid: &[T],
+    width: usize,
+    connectivity: Connectivity,
+    foreground: P,
+    same: F,
+) -> Labels
+where
+    P: Fn(&T) -> bool,
+    F: Fn(&T, &T) -> bool,
+{
+    let height = grid.len().checked_div(width).unwrap_or(0);
+    assert_eq!(width * height, grid.len(), "the grid isn't made of whole rows");
+    // the neighbours visited before a cell, as (row, col) offsets
+    let previous: &[(isize, isize)] = match connectivity {
+        Connectivity::Four => &[(0, -1), (-1, 0)],
+        Connectivity::Eight => &[(0, -1), (-1, -1), (-1, 0), (-1, 1)],
+    };
+    let mut ds = DisjointSet::new(grid.len());
+    for row in 0..height {
+        for col in 0..width {
+            let idx = row * width + col;
+            if !foreground(&grid[idx]) { continue }
+            for (d_row, d_col) in previous {
+                let (Some(r), Some(c)) = (
+                    row.checked_add_signed(*d_row),
+                    col.checked_add_signed(*d_col),
+                ) else { continue };
+                if c >= width { continue }
+                let neighbour = r * width + c;
+                if foreground(&grid[neighbour]) && same(&grid[idx], &grid[neighbour]) {
+                    ds.union(idx, neighbour);
+                }
+            }
+        }
+    }
+    // `root_labels[root]`: the label of the set of `root`
+    let mut root_labels: Vec<Option<usize>> = vec![None; grid.len()];
+    let mut labels = vec![None; grid.len()];
+    let mut components: Vec<Component> = vec![];
+    for row in 0..height {
+        for col in 0..width {
+            let idx = row * width + col;
+            if !foreground(&grid[idx]) { continue }
+            let root = ds.find(idx);
+            let label = *root_labels[root].get_or_insert_with(|| {
+                let bounds = BoundingBox { min_row: row, min_col: col, max_row: row, max_col: col };
+                components.push(Component { size: 0, bounds });
+                components.len() - 1
+            });
+            labels[idx] = Some(label);
+            let component = &mut components[label];
+            component.size += 1;
+            let bounds = &mut component.bounds;
+            // rows only ever grow in raster order
+            bounds.max_row = row;
+            bounds.min_col = bounds.min_col.min(col);
+            bounds.max_col = bounds.max_col.max(col);
+        }
+    }
+    Labels { width, height, labels, components }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::{BoundingBox, Component, Connectivity};
+
+    fn mask(rows: &[&str]) -> (Vec<bool>, usize) {
+        let cells = rows.iter().flat_map(|row| row.chars().map(|c| c == '#')).collect();
+        (cells, rows[0].len())
+    }
+
+    #[test]
+    fn label_mask() {
+        let (cells, width) = mask(&[
+            "##..#",
+            "#..#.",
+            "..##.",
+            "#....",
+        ]);
+        let four = super::label_mask(&cells, width, Connectivity::Four);
+        let sizes: Vec<usize> = four.components.iter().map(|c| c.size).collect();
+        assert_eq!(sizes, vec![3, 1, 3, 1]);
+        assert_eq!(four.get(0, 2), None);
+        assert_eq!(four.get(1, 3), Some(2));
+        assert_eq!(four.get(2, 2), Some(2));
+        assert_eq!(
+            four.components[2].bounds,
+            BoundingBox { min_row: 1, min_col: 2, max_row: 2, max_col: 3 },
+        );
+        // diagonals join the top right cell to the middle component
+        let eight = super::label_mask(&cells, width, Connectivity::Eight);
+        assert_eq!(
+            eight.components,
+            vec![
+                Component {
+                    size: 3,
+                    bounds: BoundingBox { min_row: 0, min_col: 0, max_row: 1, max_col: 1 },
+                },
+                Component {
+                    size: 4,
+                    bounds: BoundingBox { min_row: 0, min_col: 2, max_row: 2, max_col: 4 },
+                },
+                Component {
+                    size: 1,
+                    bounds: BoundingBox { min_row: 3, min_col: 0, max_row: 3, max_col: 0 },
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn label_regions() {
+        // regions of equal values, every cell labelled
+        let grid = [
+            1, 1, 2,
+            3, 1, 2,
+            3, 3, 1,
+        ];
+        let labels = super::label(&grid, 3, Connectivity::Four, |a, b| a == b);
+        let expected = [0, 0, 1, 2, 0, 1, 2, 2, 3].map(Some);
+        assert_eq!(labels.labels, expected);
+        // close enough values are the same region
+        let labels = super::label(&grid, 3, Connectivity::Eight, |a: &i32, b: &i32| a.abs_diff(*b) <= 1);
+        assert_eq!(labels.components.len(), 1);
+        let empty = super::label_mask(&[], 0, Connectivity::Four);
+        assert!(empty.components.is_empty());
+    }
+
+    /// Label by flood fill from each unlabelled cell.
+    fn flood_fill(cells: &[bool], width: usize, connectivity: Connectivity) -> Vec<Option<usize>> {
+        let height = cells.len() / width;
+        let mut labels = vec![None; cells.len()];
+        let mut next = 0;
+        for start in 0..cells.len() {
+            if !cells[start] || labels[start].is_some() { continue }
+            labels[start] = Some(next);
+            let mut stack = vec![start];
+            while let Some(idx) = stack.pop() {
+                let (row, col) = ((idx / width) as isize, (idx % width) as isize);
+                for d_row in -1..=1_isize {
+                    for d_col in -1..=1_isize {
+                        if connectivity == Connectivity::Four && d_row != 0 && d_col != 0 { continue }
+                        let (r, c) = (row + d_row, col + d_col);
+                        if r < 0 || c < 0 || r >= height as isize || c >= width as isize { continue }
+                        let neighbour = r as usize * width + c as usize;
+                        if cells[neighbour] && labels[neighbour].is_none() {
+                            labels[neighbour] = Some(next);
+                            stack.push(neighbour);
+                        }
+                    }
+                }
+            }
+            next += 1;
+        }
+        labels
+    }
+
+    #[test]
+    fn matches_flood_fill() {
+        let mut rng = StdRng::seed_from_u64(83);
+        let (width, height) = (37, 23);
+        for density in [0.3, 0.5, 0.6] {
+            let cells: Vec<bool> = (0..width * height).map(|_| rng.gen_bool(density)).collect();
+            for connectivity in [Connectivity::Four, Connectivity::Eight] {
+                let labels = super::label_mask(&cells, width, connectivity);
+                // both number the components in order of their first cell
+                assert_eq!(labels.labels, flood_fill(&cells, width, connectivity));
+                let total: usize = labels.components.iter().map(|c| c.size).sum();
+                assert_eq!(total, cells.iter().filter(|c| **c).count());
+            }
+        }
+    }
+}
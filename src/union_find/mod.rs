@@ -1,2 +1,11 @@
+pub mod concurrent;
+pub mod disjoint_set;
 pub mod first;
+pub mod labelling;
+pub mod offline_connectivity;
+pub mod percolation;
+pub mod rollback;
 pub mod second;
+pub mod weighted;
+
+pub use disjoint_set::DisjointSet;
@@ -0,0 +1,188 @@
+//! Offline dynamic connectivity: answering "are `u` and `v` connected?" queries on a graph
+//! whose edges are added and removed over time, given the whole timeline up front.
+//!
+//! Each edge is present over an interval of the timeline, from its addition to its
+//! removal. Those intervals are stored in a segment tree over time, where every interval
+//! is split over O(log T) nodes. Walking the tree depth first, the edges of a node are
+//! united on the way down and rolled back on the way up, so at each leaf the disjoint set
+//! holds exactly the edges present at that time. With `RollbackDisjointSet`, which does
+//! O(log n) finds, this takes O(T log T log n) time for `T` queries overall.
+
+use std::{collections::HashMap, ops::Range};
+
+use crate::{error::Error, union_find::rollback::RollbackDisjointSet};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Query {
+    Add(usize, usize),
+    Remove(usize, usize),
+    Connected(usize, usize),
+}
+
+
+/// Answer every `Query::Connected` in `queries`, in order, for a graph on the vertices
+/// `0..n`. Edges are undirected, and may be added more than once, in which case each
+/// removal only takes away one of the copies. Fails when a vertex is out of range, or an
+/// edge is removed that isn't there.
+pub fn solve(n: usize, queries: &[Query]) -> Result<Vec<bool>, Error> {
+    let len = queries.len();
+    // `tree[node]`: the edges present over the whole time range of `node`
+    let mut tree: Vec<Vec<(usize, usize)>> = vec![vec![]; 4 * len.max(1)];
+    // the times at which the copies of each edge that are present were added
+    let mut added: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (time, query) in queries.iter().enumerate() {
+        let (u, v) = match query {
+            Query::Add(u, v) | Query::Remove(u, v) | Query::Connected(u, v) => (*u, *v),
+        };
+        for x in [u, v] {
+            if x >= n {
+                return Err(Error::IndexOutOfRange { index: x, len: n })
+            }
+        }
+        let edge = (u.min(v), u.max(v));
+        match query {
+            Query::Add(..) => added.entry(edge).or_default().push(time),
+            Query::Remove(..) => {
+                let start = added.get_mut(&edge)
+                    .and_then(|times| times.pop())
+                    .ok_or(Error::UnknownEdge { u, v })?;
+                insert(&mut tree, 1, 0, len, start..time, edge);
+            },
+            Query::Connected(..) => {},
+        }
+    }
+    // edges that are never removed last until the end
+    for (edge, times) in added {
+        for start in times {
+            insert(&mut tree, 1, 0, len, start..len, edge);
+        }
+    }
+    let mut answers = vec![];
+    if len > 0 {
+        let mut ds = RollbackDisjointSet::new(n);
+        walk(&tree, 1, 0, len, queries, &mut ds, &mut answers);
+    }
+    Ok(answers)
+}
+
+
+/// Add `edge` to the nodes that make up `range`, in the subtree of `node`, which covers
+/// the times `lo..hi`.
+fn insert(
+    tree: &mut [Vec<(usize, usize)>],
+    node: usize,
+    lo: usize,
+    hi: usize,
+    range: Range<usize>,
+    edge: (usize, usize),
+) {
+    if range.end <= lo || hi <= range.start {
+        return
+    }
+    if range.start <= lo && hi <= range.end {
+        tree[node].push(edge);
+        return
+    }
+    let mid = lo + (hi - lo) / 2;
+    insert(tree, 2 * node, lo, mid, range.clone(), edge);
+    insert(tree, 2 * node + 1, mid, hi, range, edge);
+}
+
+
+fn walk(
+    tree: &[Vec<(usize, usize)>],
+    node: usize,
+    lo: usize,
+    hi: usize,
+    queries: &[Query],
+    ds: &mut RollbackDisjointSet,
+    answers: &mut Vec<bool>,
+) {
+    let snapshot = ds.snapshot();
+    for (u, v) in &tree[node] {
+        ds.union(*u, *v);
+    }
+    if hi - lo == 1 {
+        if let Query::Connected(u, v) = queries[lo] {
+            answers.push(ds.connected(u, v));
+        }
+    } else {
+        let mid = lo + (hi - lo) / 2;
+        walk(tree, 2 * node, lo, mid, queries, ds, answers);
+        walk(tree, 2 * node + 1, mid, hi, queries, ds, answers);
+    }
+    ds.rollback(snapshot);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::{error::Error, union_find::DisjointSet};
+    use super::Query::{self, Add, Connected, Remove};
+
+    #[test]
+    fn solve() {
+        let queries = vec![
+            Connected(0, 1),
+            Add(0, 1),
+            Add(1, 2),
+            Connected(0, 2),
+            Add(2, 0),
+            Remove(0, 1),
+            Connected(0, 1),
+            Remove(1, 2),
+            Connected(0, 1),
+            Connected(2, 0),
+            Add(3, 3),
+            Connected(3, 3),
+        ];
+        let expected = vec![false, true, true, false, true, true];
+        assert_eq!(super::solve(4, &queries), Ok(expected));
+        assert_eq!(super::solve(0, &[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(super::solve(3, &[Add(0, 3)]), Err(Error::IndexOutOfRange { index: 3, len: 3 }));
+        assert_eq!(
+            super::solve(3, &[Add(0, 1), Remove(1, 0), Remove(0, 1)]),
+            Err(Error::UnknownEdge { u: 0, v: 1 }),
+        );
+    }
+
+    #[test]
+    fn matches_rebuild() {
+        // rebuild the connectivity from the edges present at every query
+        let n = 30;
+        let mut rng = StdRng::seed_from_u64(71);
+        let mut edges: Vec<(usize, usize)> = vec![];
+        let mut queries: Vec<Query> = vec![];
+        let mut expected = vec![];
+        for _ in 0..1500 {
+            match rng.gen_range(0..3) {
+                0 => {
+                    let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                    edges.push((u, v));
+                    queries.push(Add(u, v));
+                },
+                1 if !edges.is_empty() => {
+                    let (u, v) = edges.swap_remove(rng.gen_range(0..edges.len()));
+                    queries.push(Remove(v, u));
+                },
+                _ => {
+                    let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                    let mut ds = DisjointSet::new(n);
+                    for (a, b) in &edges {
+                        ds.union(*a, *b);
+                    }
+                    expected.push(ds.connected(u, v));
+                    queries.push(Connected(u, v));
+                },
+            }
+        }
+        assert_eq!(super::solve(n, &queries), Ok(expected));
+    }
+}
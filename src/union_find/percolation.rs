@@ -0,0 +1,336 @@
+//! Monte Carlo percolation on top of the union-find.
+//!
+//! On an `L x L` square grid, either every site is open with probability `p` and open
+//! neighbours are connected (site percolation), or every site is there and each bond
+//! between neighbours is open with probability `p` (bond percolation). The grid
+//! percolates when some open cluster touches both its top and bottom rows. Two extra
+//! virtual sites, joined to every site of the top and the bottom row, turn that into a
+//! single `connected()` check.
+//! As `L` grows, the probability of percolating jumps from 0 to 1 around a threshold `p_c`:
+//! about 0.5927 for sites, and exactly 1/2 for bonds. `Simulation::threshold()` estimates
+//! it the way Newman and Ziff do: open the sites (or bonds) one at a time, in a random
+//! order, and record the fraction that was open when the grid first percolated.
+//!
+//! The same happens in random graphs with `n` vertices and `mean_degree * n / 2` random
+//! edges: past a mean degree of 1, a giant component with a fixed fraction of the
+//! vertices appears.
+//!
+//! Every trial is a long sequence of unions and finds, which also makes these a workload
+//! for comparing the union-find strategies.
+
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+use crate::union_find::{DisjointSet, disjoint_set::{Compression, Union}};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lattice {
+    Site,
+    Bond,
+}
+
+
+/// The sample mean and standard deviation of some quantity over independent trials.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub trials: usize,
+}
+
+
+impl Estimate {
+    fn new(samples: &[f64]) -> Self {
+        let trials = samples.len();
+        let mean = samples.iter().sum::<f64>() / trials as f64;
+        let variance = if trials > 1 {
+            samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (trials - 1) as f64
+        } else {
+            0.0
+        };
+        Self { mean, std_dev: variance.sqrt(), trials }
+    }
+
+    /// The standard error of the mean.
+    pub fn std_error(&self) -> f64 {
+        self.std_dev / (self.trials as f64).sqrt()
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridSummary {
+    /// The fraction of trials in which the grid percolated.
+    pub percolation_probability: f64,
+    /// The fraction of all sites in the largest cluster.
+    pub giant_component: Estimate,
+}
+
+
+/// Runs trials from a fixed seed, so that the results are reproducible, with the
+/// given union-find strategies.
+#[derive(Debug, Clone, Copy)]
+pub struct Simulation {
+    seed: u64,
+    union: Union,
+    compression: Compression,
+}
+
+
+/// A square grid of `size x size` sites, in row-major order, followed by the virtual
+/// top and bottom sites.
+struct Grid {
+    size: usize,
+    ds: DisjointSet,
+}
+
+
+impl Grid {
+    fn new(size: usize, union: Union, compression: Compression) -> Self {
+        Self { size, ds: DisjointSet::with_strategy(size * size + 2, union, compression) }
+    }
+
+    fn top(&self) -> usize {
+        self.size * self.size
+    }
+
+    fn bottom(&self) -> usize {
+        self.size * self.size + 1
+    }
+
+    fn percolates(&mut self) -> bool {
+        let (top, bottom) = (self.top(), self.bottom());
+        self.ds.connected(top, bottom)
+    }
+
+    /// Connect `site` to the virtual site of its row, if it's at the top or bottom.
+    fn connect_edge(&mut self, site: usize) {
+        if site < self.size {
+            let top = self.top();
+            self.ds.union(site, top);
+        }
+        if site >= self.size * (self.size - 1) {
+            let bottom = self.bottom();
+            self.ds.union(site, bottom);
+        }
+    }
+
+    /// The neighbours of `site` to its right and below.
+    fn forward_neighbours(&self, site: usize) -> impl Iterator<Item = usize> {
+        let (row, col) = (site / self.size, site % self.size);
+        let right = (col + 1 < self.size).then_some(site + 1);
+        let down = (row + 1 < self.size).then_some(site + self.size);
+        right.into_iter().chain(down)
+    }
+
+    /// Every bond, as the pair of sites it joins.
+    fn bonds(&self) -> Vec<(usize, usize)> {
+        (0..self.size * self.size)
+            .flat_map(|site| self.forward_neighbours(site).map(move |n| (site, n)))
+            .collect()
+    }
+
+    /// The size of the largest cluster of the sites for which `include` is true, as a
+    /// fraction of all sites.
+    fn giant_component(&mut self, include: impl Fn(usize) -> bool) -> f64 {
+        let n = self.size * self.size;
+        largest_set(&mut self.ds, (0..n).filter(|site| include(*site))) as f64 / n as f64
+    }
+}
+
+
+/// The size of the largest set, counting only the given elements.
+fn largest_set(ds: &mut DisjointSet, elements: impl Iterator<Item = usize>) -> usize {
+    let mut counts = vec![0; ds.len()];
+    let mut largest = 0;
+    for x in elements {
+        let root = ds.find(x);
+        counts[root] += 1;
+        largest = largest.max(counts[root]);
+    }
+    largest
+}
+
+
+impl Simulation {
+    pub fn new(seed: u64) -> Self {
+        Self::with_strategy(seed, Union::default(), Compression::default())
+    }
+
+    pub fn with_strategy(seed: u64, union: Union, compression: Compression) -> Self {
+        Self { seed, union, compression }
+    }
+
+    fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+
+    /// Run `trials` trials of percolation on a `size x size` grid, with every site or
+    /// bond open with probability `p`.
+    pub fn grid(&self, size: usize, p: f64, lattice: Lattice, trials: usize) -> GridSummary {
+        assert!(size > 0 && trials > 0, "need at least one site and one trial");
+        let mut rng = self.rng();
+        let mut percolated = 0;
+        let mut giant = Vec::with_capacity(trials);
+        for _ in 0..trials {
+            let mut grid = Grid::new(size, self.union, self.compression);
+            let open: Vec<bool> = match lattice {
+                Lattice::Site => (0..size * size).map(|_| rng.gen_bool(p)).collect(),
+                Lattice::Bond => vec![true; size * size],
+            };
+            for (a, b) in grid.bonds() {
+                let connect = match lattice {
+                    Lattice::Site => open[a] && open[b],
+                    Lattice::Bond => rng.gen_bool(p),
+                };
+                if connect {
+                    grid.ds.union(a, b);
+                }
+            }
+            // before the virtual sites join the top and bottom rows into one cluster
+            giant.push(grid.giant_component(|site| open[site]));
+            for site in (0..size * size).filter(|site| open[*site]) {
+                grid.connect_edge(site);
+            }
+            if grid.percolates() {
+                percolated += 1;
+            }
+        }
+        GridSummary {
+            percolation_probability: percolated as f64 / trials as f64,
+            giant_component: Estimate::new(&giant),
+        }
+    }
+
+    /// Estimate the percolation threshold of a `size x size` grid over `trials` trials.
+    pub fn threshold(&self, size: usize, lattice: Lattice, trials: usize) -> Estimate {
+        assert!(size > 0 && trials > 0, "need at least one site and one trial");
+        let mut rng = self.rng();
+        let mut thresholds = Vec::with_capacity(trials);
+        for _ in 0..trials {
+            let mut grid = Grid::new(size, self.union, self.compression);
+            let threshold = match lattice {
+                Lattice::Site => {
+                    let mut order: Vec<usize> = (0..size * size).collect();
+                    order.shuffle(&mut rng);
+                    let mut open = vec![false; size * size];
+                    let mut opened = 0;
+                    for site in order {
+                        open[site] = true;
+                        opened += 1;
+                        grid.connect_edge(site);
+                        let (row, col) = (site / size, site % size);
+                        let neighbours = [
+                            (col > 0).then(|| site - 1),
+                            (col + 1 < size).then_some(site + 1),
+                            (row > 0).then(|| site - size),
+                            (row + 1 < size).then_some(site + size),
+                        ];
+                        for neighbour in neighbours.into_iter().flatten() {
+                            if open[neighbour] {
+                                grid.ds.union(site, neighbour);
+                            }
+                        }
+                        if grid.percolates() { break }
+                    }
+                    opened as f64 / (size * size) as f64
+                },
+                Lattice::Bond => {
+                    for site in 0..size * size {
+                        grid.connect_edge(site);
+                    }
+                    let mut bonds = grid.bonds();
+                    // a single site percolates on its own
+                    if bonds.is_empty() {
+                        0.0
+                    } else {
+                        bonds.shuffle(&mut rng);
+                        let mut opened = 0;
+                        for (a, b) in &bonds {
+                            grid.ds.union(*a, *b);
+                            opened += 1;
+                            if grid.percolates() { break }
+                        }
+                        opened as f64 / bonds.len() as f64
+                    }
+                },
+            };
+            thresholds.push(threshold);
+        }
+        Estimate::new(&thresholds)
+    }
+
+    /// Estimate the fraction of the `n` vertices of a random graph in its largest
+    /// component, where the graph has `mean_degree * n / 2` edges between uniformly
+    /// random pairs of vertices.
+    pub fn random_graph(&self, n: usize, mean_degree: f64, trials: usize) -> Estimate {
+        assert!(n > 0 && trials > 0, "need at least one vertex and one trial");
+        let mut rng = self.rng();
+        let n_edges = (mean_degree * n as f64 / 2.0).round() as usize;
+        let mut giant = Vec::with_capacity(trials);
+        for _ in 0..trials {
+            let mut ds = DisjointSet::with_strategy(n, self.union, self.compression);
+            for _ in 0..n_edges {
+                ds.union(rng.gen_range(0..n), rng.gen_range(0..n));
+            }
+            giant.push(largest_set(&mut ds, 0..n) as f64 / n as f64);
+        }
+        Estimate::new(&giant)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::union_find::disjoint_set::{Compression, Union};
+    use super::{Lattice, Simulation};
+
+    #[test]
+    fn grid_extremes() {
+        let sim = Simulation::new(89);
+        for lattice in [Lattice::Site, Lattice::Bond] {
+            let closed = sim.grid(10, 0.0, lattice, 5);
+            assert_eq!(closed.percolation_probability, 0.0);
+            let open = sim.grid(10, 1.0, lattice, 5);
+            assert_eq!(open.percolation_probability, 1.0);
+            assert_eq!(open.giant_component.mean, 1.0);
+            assert_eq!(open.giant_component.std_dev, 0.0);
+        }
+        // isolated sites
+        assert_eq!(sim.grid(10, 0.0, Lattice::Bond, 1).giant_component.mean, 0.01);
+        assert_eq!(sim.grid(1, 1.0, Lattice::Site, 1).percolation_probability, 1.0);
+    }
+
+    #[test]
+    fn thresholds() {
+        let sim = Simulation::new(97);
+        let site = sim.threshold(48, Lattice::Site, 40);
+        assert!((site.mean - 0.5927).abs() < 0.03, "{site:?}");
+        let bond = sim.threshold(48, Lattice::Bond, 40);
+        assert!((bond.mean - 0.5).abs() < 0.03, "{bond:?}");
+        // well below and above the threshold
+        assert!(sim.grid(48, 0.45, Lattice::Site, 20).percolation_probability < 0.1);
+        assert!(sim.grid(48, 0.75, Lattice::Site, 20).percolation_probability > 0.9);
+    }
+
+    #[test]
+    fn random_graph() {
+        let sim = Simulation::new(101);
+        let sparse = sim.random_graph(4000, 0.5, 10);
+        assert!(sparse.mean < 0.01, "{sparse:?}");
+        // the giant component is the solution of s = 1 - exp(-2s): about 0.797
+        let dense = sim.random_graph(4000, 2.0, 10);
+        assert!((dense.mean - 0.797).abs() < 0.03, "{dense:?}");
+    }
+
+    #[test]
+    fn reproducible_across_strategies() {
+        let expected = Simulation::new(103).threshold(20, Lattice::Site, 10);
+        assert_eq!(Simulation::new(103).threshold(20, Lattice::Site, 10), expected);
+        let strategies = [(Union::ByRank, Compression::Halving), (Union::BySize, Compression::None)];
+        for (union, compression) in strategies {
+            let sim = Simulation::with_strategy(103, union, compression);
+            assert_eq!(sim.threshold(20, Lattice::Site, 10), expected);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! A disjoint set whose unions can be undone, most recent first. `find()` doesn't
+//! compress paths, since that would change the trees in ways that aren't undone, so the
+//! trees are kept O(log n) deep by union by size alone, and `find()` takes O(log n).
+//! Every union that merges two sets is pushed onto a history stack. `snapshot()` marks the
+//! current point in the history, and `rollback()` pops and reverts unions back to it.
+//! This is what offline dynamic connectivity and backtracking searches need.
+
+
+/// A point in the history of a `RollbackDisjointSet`, to roll back to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The length of the history when the snapshot was taken.
+    len: usize,
+    /// The id of the last union in the history then, or 0 if it was empty, so that a
+    /// history that was rolled back past the snapshot and regrown can be told apart.
+    last: u64,
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct RollbackDisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    /// The `(root, child, id)` of every merge, in order. The child root was attached
+    /// under the root, and `id` is unique to the merge.
+    history: Vec<(usize, usize, u64)>,
+    /// The number of merges ever made, including undone ones. The last one's id.
+    unions: u64,
+    count: usize,
+}
+
+
+impl RollbackDisjointSet {
+    /// `len` elements, each in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            history: vec![],
+            unions: 0,
+            count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The representative of the set containing `x`. Panics if `x >= len()`.
+    pub fn find(&self, x: usize) -> usize {
+        let mut x = x;
+        while self.parents[x] != x {
+            x = self.parents[x];
+        }
+        x
+    }
+
+    /// Merge the sets containing `a` and `b`. Returns `false` if they already were the
+    /// same set, in which case nothing is added to the history.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let mut root_a = self.find(a);
+        let mut root_b = self.find(b);
+        if root_a == root_b {
+            return false
+        }
+        if self.sizes[root_a] < self.sizes[root_b] {
+            std::mem::swap(&mut root_a, &mut root_b);
+        }
+        self.parents[root_b] = root_a;
+        self.sizes[root_a] += self.sizes[root_b];
+        self.unions += 1;
+        self.history.push((root_a, root_b, self.unions));
+        self.count -= 1;
+        true
+    }
+
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of elements in the set containing `x`.
+    pub fn size(&self, x: usize) -> usize {
+        self.sizes[self.find(x)]
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            len: self.history.len(),
+            last: self.history.last().map_or(0, |&(_, _, id)| id),
+        }
+    }
+
+    /// Undo every union since `snapshot` was taken. Panics if the history has been rolled
+    /// back past `snapshot` since, even if it has grown again, as the history it refers to
+    /// no longer exists.
+    pub fn rollback(&mut self, snapshot: Snapshot) {
+        let last = match snapshot.len {
+            0 => Some(0),
+            len => self.history.get(len - 1).map(|&(_, _, id)| id),
+        };
+        assert_eq!(last, Some(snapshot.last), "snapshot is stale");
+        while self.history.len() > snapshot.len {
+            self.undo();
+        }
+    }
+
+    /// Undo the most recent union. Returns `false` if there was none.
+    pub fn undo(&mut self) -> bool {
+        let Some((root, child, _)) = self.history.pop() else {
+            return false
+        };
+        self.parents[child] = child;
+        self.sizes[root] -= self.sizes[child];
+        self.count += 1;
+        true
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::union_find::DisjointSet;
+    use super::RollbackDisjointSet;
+
+    #[test]
+    fn rollback() {
+        let mut ds = RollbackDisjointSet::new(6);
+        ds.union(0, 1);
+        let start = ds.snapshot();
+        assert!(ds.union(1, 2));
+        assert!(!ds.union(0, 2));
+        let inner = ds.snapshot();
+        assert!(ds.union(3, 4));
+        assert!(ds.union(4, 0));
+        assert_eq!((ds.count(), ds.size(3)), (2, 5));
+        ds.rollback(inner);
+        assert_eq!((ds.count(), ds.size(0)), (4, 3));
+        assert!(ds.connected(0, 2));
+        assert!(!ds.connected(3, 4));
+        ds.rollback(start);
+        assert!(ds.connected(0, 1));
+        assert!(!ds.connected(1, 2));
+        assert!(ds.undo());
+        assert_eq!(ds.count(), 6);
+        assert!(!ds.undo());
+    }
+
+    #[test]
+    #[should_panic(expected = "snapshot is stale")]
+    fn stale_snapshot() {
+        let mut ds = RollbackDisjointSet::new(3);
+        let start = ds.snapshot();
+        ds.union(0, 1);
+        let stale = ds.snapshot();
+        ds.rollback(start);
+        ds.rollback(stale);
+    }
+
+    #[test]
+    #[should_panic(expected = "snapshot is stale")]
+    fn stale_snapshot_regrown() {
+        // the history is back to the same length, but it isn't the same history
+        let mut ds = RollbackDisjointSet::new(4);
+        ds.union(0, 1);
+        let stale = ds.snapshot();
+        ds.undo();
+        ds.union(2, 3);
+        ds.rollback(stale);
+    }
+
+    #[test]
+    fn matches_rebuild() {
+        // after any sequence of unions and rollbacks, the sets are the same as if only
+        // the unions that weren't rolled back were made
+        let n = 200;
+        let mut rng = StdRng::seed_from_u64(59);
+        let mut ds = RollbackDisjointSet::new(n);
+        let mut kept: Vec<(usize, usize)> = vec![];
+        let mut snapshots = vec![];
+        for _ in 0..2000 {
+            match rng.gen_range(0..10) {
+                0 => snapshots.push((ds.snapshot(), kept.len())),
+                1 if !snapshots.is_empty() => {
+                    let (snapshot, n_kept) = snapshots.pop().unwrap();
+                    ds.rollback(snapshot);
+                    kept.truncate(n_kept);
+                },
+                _ => {
+                    let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                    ds.union(a, b);
+                    kept.push((a, b));
+                },
+            }
+            let mut expected = DisjointSet::new(n);
+            for (a, b) in &kept {
+                expected.union(*a, *b);
+            }
+            assert_eq!(ds.count(), expected.count());
+            let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            assert_eq!(ds.connected(a, b), expected.connected(a, b));
+            assert_eq!(ds.size(a), expected.size(a));
+        }
+    }
+}
@@ -1,45 +1,33 @@
 //! Second time implementing union-find.
 
+use crate::{error::Error, union_find::{DisjointSet, disjoint_set::{Compression, Union}}};
 
+
+/// Union by rank with path halving, on top of the shared `union_find::DisjointSet`, with
+/// out of range indices reported rather than panicking.
 struct UnionFind {
-    parents: Vec<usize>,
-    ranks: Vec<usize>,
+    ds: DisjointSet,
 }
 
 
 impl UnionFind {
     fn new(n_vertices: usize) -> Self {
-        Self {
-            parents: (0..n_vertices).collect(),
-            ranks: vec![0; n_vertices],
-        }
+        Self { ds: DisjointSet::with_strategy(n_vertices, Union::ByRank, Compression::Halving) }
     }
 
     fn find(&mut self, idx: usize) -> Option<usize> {
-        let parent_idx = self.parents.get(idx)?;
-        if parent_idx != &idx {
-            self.parents[idx] = self.find(self.parents[idx])?;
-            return Some(self.parents[idx])
-        }
-        Some(*parent_idx)
+        if idx >= self.ds.len() { return None }
+        Some(self.ds.find(idx))
     }
 
-    fn union(&mut self, a: usize, b: usize) -> Result<(), String> {
-        let mut root1 = match self.find(a) {
-            None => return Err(String::from("unable to find `a`")),
-            Some(root) => root,
-        };
-        let mut root2 = match self.find(b) {
-            None => return Err(String::from("unable to find `b`")),
-            Some(root) => root,
-        };
-        if root1 == root2 { return Ok(()) }
-        match self.ranks[root1].cmp(&self.ranks[root2]) {
-            std::cmp::Ordering::Less => std::mem::swap(&mut root1, &mut root2),
-            std::cmp::Ordering::Equal=> self.ranks[root1] += 1,
-            _ => {}
+    fn union(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        let len = self.ds.len();
+        for idx in [a, b] {
+            if idx >= len {
+                return Err(Error::IndexOutOfRange { index: idx, len })
+            }
         }
-        self.parents[root2] = root1;
+        self.ds.union(a, b);
         Ok(())
     }
 }
@@ -157,4 +145,13 @@ mod tests {
         assert_eq!(mst, expected_mst);
     }
 
+    #[test]
+    fn out_of_range() {
+        use crate::error::Error;
+        let mut uf = super::UnionFind::new(3);
+        assert_eq!(uf.union(0, 2), Ok(()));
+        assert_eq!(uf.find(2), uf.find(0));
+        assert_eq!(uf.find(3), None);
+        assert_eq!(uf.union(1, 3), Err(Error::IndexOutOfRange { index: 3, len: 3 }));
+    }
 }
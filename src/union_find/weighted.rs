@@ -0,0 +1,263 @@
+//! A disjoint set that also records how the elements of each set relate to each other:
+//! every element has a potential relative to the root of its set, so the structure can
+//! hold constraints like "x - y = w", and tell whether a new one is consistent with them.
+//! The potentials can be any abelian group, e.g.:
+//! - integers under addition, for relative measurements ("a is 3 heavier than b").
+//! - `Parity`, for "x and y are on the same / opposite sides", which detects an odd cycle
+//!   (and so a graph that isn't bipartite) as soon as its last edge is added.
+
+use std::fmt::Debug;
+
+use crate::error::Error;
+
+
+/// An abelian group: `combine()` must be associative and commutative, with `identity()`
+/// as its identity element and `inverse()` giving inverses.
+pub trait Group: Copy + PartialEq + Debug {
+    fn identity() -> Self;
+
+    fn combine(self, other: Self) -> Self;
+
+    fn inverse(self) -> Self;
+}
+
+
+macro_rules! impl_group {
+    ($($t:ty),*) => {
+        $(
+            /// Addition, wrapping on overflow so that it is always a group.
+            impl Group for $t {
+                fn identity() -> Self {
+                    0
+                }
+
+                fn combine(self, other: Self) -> Self {
+                    self.wrapping_add(other)
+                }
+
+                fn inverse(self) -> Self {
+                    self.wrapping_neg()
+                }
+            }
+        )*
+    };
+}
+
+
+impl_group!(i8, i16, i32, i64, i128, isize);
+
+
+/// Whether two elements are on the same side (`Even`) or opposite sides (`Odd`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+
+impl Group for Parity {
+    fn identity() -> Self {
+        Parity::Even
+    }
+
+    fn combine(self, other: Self) -> Self {
+        if self == other { Parity::Even } else { Parity::Odd }
+    }
+
+    fn inverse(self) -> Self {
+        self
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct WeightedDisjointSet<G> {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    /// `potentials[x]`: the value of `x` relative to its parent, i.e. `x - parent`. Always
+    /// the identity for roots.
+    potentials: Vec<G>,
+    count: usize,
+}
+
+
+impl<G: Group> WeightedDisjointSet<G> {
+    /// `len` elements, each in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            potentials: vec![G::identity(); len],
+            count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The root of the set containing `x`, and `x - root`. Panics if `x >= len()`.
+    pub fn find(&mut self, x: usize) -> (usize, G) {
+        let mut path = vec![];
+        let mut root = x;
+        while self.parents[root] != root {
+            path.push(root);
+            root = self.parents[root];
+        }
+        // from the top down, so every parent is already relative to the root
+        for node in path.into_iter().rev() {
+            let parent = self.parents[node];
+            self.potentials[node] = self.potentials[node].combine(self.potentials[parent]);
+            self.parents[node] = root;
+        }
+        (root, self.potentials[x])
+    }
+
+    /// Record that `a - b = w`, merging their sets if they were different. Returns whether
+    /// they were merged, or `Error::Contradiction` if they were already in the same set
+    /// with a different `a - b`, in which case nothing changes.
+    pub fn union(&mut self, a: usize, b: usize, w: G) -> Result<bool, Error> {
+        let (mut root_a, potential_a) = self.find(a);
+        let (mut root_b, potential_b) = self.find(b);
+        // root_b - root_a = (a - root_a) - (b - root_b) - (a - b)
+        let mut offset = potential_a.combine(potential_b.inverse()).combine(w.inverse());
+        if root_a == root_b {
+            if offset != G::identity() {
+                return Err(Error::Contradiction)
+            }
+            return Ok(false)
+        }
+        if self.sizes[root_a] < self.sizes[root_b] {
+            std::mem::swap(&mut root_a, &mut root_b);
+            offset = offset.inverse();
+        }
+        self.parents[root_b] = root_a;
+        self.potentials[root_b] = offset;
+        self.sizes[root_a] += self.sizes[root_b];
+        self.count -= 1;
+        Ok(true)
+    }
+
+    /// `a - b`, if it's determined by the constraints so far.
+    pub fn diff(&mut self, a: usize, b: usize) -> Option<G> {
+        let (root_a, potential_a) = self.find(a);
+        let (root_b, potential_b) = self.find(b);
+        if root_a != root_b {
+            return None
+        }
+        Some(potential_a.combine(potential_b.inverse()))
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a).0 == self.find(b).0
+    }
+
+    /// The number of elements in the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x).0;
+        self.sizes[root]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::error::Error;
+    use super::{Parity, WeightedDisjointSet};
+
+    #[test]
+    fn relative_measurements() {
+        // weights of 5 items, measured against each other
+        let mut ds = WeightedDisjointSet::<i64>::new(5);
+        assert_eq!(ds.union(0, 1, 3), Ok(true));
+        assert_eq!(ds.union(2, 1, -4), Ok(true));
+        assert_eq!(ds.union(3, 4, 10), Ok(true));
+        assert_eq!(ds.diff(0, 2), Some(7));
+        assert_eq!(ds.diff(2, 0), Some(-7));
+        assert_eq!(ds.diff(0, 3), None);
+        // consistent with what's known already
+        assert_eq!(ds.union(0, 2, 7), Ok(false));
+        assert_eq!(ds.union(0, 2, 6), Err(Error::Contradiction));
+        assert_eq!(ds.union(4, 2, 1), Ok(true));
+        assert_eq!(ds.diff(3, 0), Some(4));
+        assert_eq!((ds.count(), ds.size(1)), (1, 5));
+    }
+
+    #[test]
+    fn hidden_values() {
+        // constraints taken from hidden values are always consistent, and every
+        // difference that's determined matches them
+        let n = 300;
+        let mut rng = StdRng::seed_from_u64(61);
+        let values: Vec<i64> = (0..n).map(|_| rng.gen_range(-1000..1000)).collect();
+        let mut ds = WeightedDisjointSet::<i64>::new(n);
+        for _ in 0..500 {
+            let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            assert!(ds.union(a, b, values[a] - values[b]).is_ok());
+            assert_eq!(ds.union(a, b, values[a] - values[b] + 1), Err(Error::Contradiction));
+            let (c, d) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            if let Some(diff) = ds.diff(c, d) {
+                assert_eq!(diff, values[c] - values[d]);
+            }
+        }
+    }
+
+    /// Whether the graph is bipartite, by 2-colouring it breadth first.
+    fn bipartite(n: usize, edges: &[(usize, usize)]) -> bool {
+        let mut adjacent = vec![vec![]; n];
+        for (u, v) in edges {
+            adjacent[*u].push(*v);
+            adjacent[*v].push(*u);
+        }
+        let mut colours: Vec<Option<bool>> = vec![None; n];
+        for start in 0..n {
+            if colours[start].is_some() { continue }
+            colours[start] = Some(false);
+            let mut queue = VecDeque::from([start]);
+            while let Some(u) = queue.pop_front() {
+                for v in &adjacent[u] {
+                    match colours[*v] {
+                        None => {
+                            colours[*v] = colours[u].map(|c| !c);
+                            queue.push_back(*v);
+                        },
+                        Some(c) if Some(c) == colours[u] => return false,
+                        _ => {},
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn online_bipartiteness() {
+        let n = 40;
+        let mut rng = StdRng::seed_from_u64(67);
+        for _ in 0..20 {
+            let mut ds = WeightedDisjointSet::new(n);
+            let mut edges = vec![];
+            loop {
+                let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                edges.push((u, v));
+                let result = ds.union(u, v, Parity::Odd);
+                // the edge that makes an odd cycle is the first to contradict
+                assert_eq!(result.is_ok(), bipartite(n, &edges));
+                if result.is_err() { break }
+            }
+        }
+    }
+}